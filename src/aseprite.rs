@@ -0,0 +1,79 @@
+//! loading of sprite sheets from aseprite files, so the art doesn't need to be exported on every
+//! change
+
+use std::path::Path;
+
+use asefile::{AnimationDirection, AsepriteFile, AsepriteParseError};
+use image::{
+    error::{DecodingError, ImageFormatHint},
+    imageops, ImageError, RgbaImage,
+};
+
+use crate::{Rect, SheetSource, SourceFormat, SpriteSheet, TileyError};
+
+impl SpriteSheet {
+    /// loads a sprite sheet from an aseprite file, every frame is a sprite with the visible
+    /// layers flattened, and the sprite ids are the frame indices. the tags become animations
+    /// with the same name, following the direction of the tag, the frame durations are ignored
+    ///
+    /// # Errors
+    ///
+    /// fails if the file can't be read or parsed
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// let sprite_sheet = SpriteSheet::from_aseprite("./resources/player.aseprite")?;
+    /// tile_grid.set_sprite_sheet(sprite_sheet);
+    /// tile_grid.draw_animation_frame((3, 4), "walk", frame_counter / 10)?;
+    /// ```
+    pub fn from_aseprite(path: impl AsRef<Path>) -> Result<Self, TileyError> {
+        let path = path.as_ref();
+        let file = AsepriteFile::read_file(path).map_err(aseprite_error)?;
+        let (width, height) = file.size();
+
+        // the frames are put side by side in a single image
+        let mut image = RgbaImage::new((width * file.num_frames() as usize) as u32, height as u32);
+        let mut regions = Vec::new();
+        for frame in 0..file.num_frames() {
+            // asefile uses another version of the image crate, the pixels are moved as raw bytes
+            let pixels = file.frame(frame).image().into_raw();
+            let frame_image = RgbaImage::from_raw(width as u32, height as u32, pixels)
+                .expect("a frame is as big as the aseprite sprite");
+
+            let x = frame as usize * width;
+            imageops::replace(&mut image, &frame_image, x as i64, 0);
+            regions.push(Rect::new((x as isize, 0), width, height));
+        }
+
+        let mut sprite_sheet = SpriteSheet::from_atlas(image, regions)?
+            .with_source(SheetSource::new(path, SourceFormat::Aseprite));
+
+        for tag in (0..file.num_tags()).map(|id| file.tag(id)) {
+            let (from, to) = (tag.from_frame() as usize, tag.to_frame() as usize);
+            let frames: Vec<usize> = match tag.animation_direction() {
+                AnimationDirection::Forward => (from..=to).collect(),
+                AnimationDirection::Reverse => (from..=to).rev().collect(),
+                AnimationDirection::PingPong => (from..=to).chain((from + 1..to).rev()).collect(),
+            };
+
+            let frames = frames.into_iter().map(|frame| (frame, 0)).collect();
+            sprite_sheet.add_animation(tag.name(), frames)?;
+        }
+
+        Ok(sprite_sheet)
+    }
+}
+
+/// io errors are kept as they are, the others are reported as a decoding error of the image
+fn aseprite_error(error: AsepriteParseError) -> TileyError {
+    match error {
+        AsepriteParseError::IoError(error) => TileyError::Io(error),
+        error => TileyError::Decode(ImageError::Decoding(DecodingError::new(
+            ImageFormatHint::Name("aseprite".to_string()),
+            error,
+        ))),
+    }
+}
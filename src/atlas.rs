@@ -0,0 +1,297 @@
+//! loading of sprite sheets packed as an atlas, from the json exported by texture packers like
+//! TexturePacker or free-tex-packer, or by aseprite
+
+use std::{collections::HashSet, fmt, fs, path::Path};
+
+use image::ImageReader;
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::{Rect, SheetSource, SourceFormat, SpriteSheet, TileyError};
+
+/// the content of an atlas file
+#[derive(Deserialize)]
+struct Atlas {
+    frames: Frames,
+    meta: Meta,
+}
+
+/// the sprites of the atlas, exported either as an object with the sprite names as keys or as an
+/// array
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Frames {
+    Hash(OrderedFrames),
+    Array(Vec<NamedFrame>),
+}
+
+/// the sprites of the "hash" format in the order of the file, because the aseprite tags refer to
+/// the sprites by position
+struct OrderedFrames(Vec<(String, Frame)>);
+
+#[derive(Deserialize)]
+struct NamedFrame {
+    filename: String,
+    #[serde(flatten)]
+    frame: Frame,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Frame {
+    frame: Region,
+    #[serde(default)]
+    rotated: bool,
+    /// the part of the original image that was kept after trimming the transparent borders
+    sprite_source_size: Option<Region>,
+    /// the size of the original image, before trimming
+    source_size: Option<Size>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct Region {
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct Size {
+    w: usize,
+    h: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Meta {
+    /// path of the atlas image, relative to the atlas file
+    image: String,
+    /// the animations exported by aseprite
+    #[serde(default)]
+    frame_tags: Vec<Tag>,
+    /// the named parts of the sprites exported by aseprite
+    #[serde(default)]
+    slices: Vec<Slice>,
+}
+
+#[derive(Deserialize)]
+struct Tag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: Direction,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+enum Direction {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+#[derive(Deserialize)]
+struct Slice {
+    name: String,
+    keys: Vec<SliceKey>,
+}
+
+#[derive(Deserialize)]
+struct SliceKey {
+    /// index of the sprite the slice is in
+    frame: usize,
+    /// position of the slice in the image of the sprite
+    bounds: Region,
+}
+
+impl<'de> Deserialize<'de> for OrderedFrames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FramesVisitor;
+
+        impl<'de> Visitor<'de> for FramesVisitor {
+            type Value = OrderedFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object with the sprites by name")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::new();
+                while let Some(frame) = map.next_entry()? {
+                    frames.push(frame);
+                }
+
+                Ok(OrderedFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_map(FramesVisitor)
+    }
+}
+
+impl Region {
+    fn to_rect(self) -> Rect {
+        Rect::new((self.x as isize, self.y as isize), self.w, self.h)
+    }
+}
+
+impl Frame {
+    /// whether the transparent borders of the sprite were cut away, so the frame is smaller than
+    /// the original image or doesn't start at its corner
+    fn is_trimmed(&self) -> bool {
+        let (w, h) = (self.frame.w, self.frame.h);
+        let moved = self
+            .sprite_source_size
+            .is_some_and(|r| (r.x, r.y, r.w, r.h) != (0, 0, w, h));
+        let resized = self.source_size.is_some_and(|s| (s.w, s.h) != (w, h));
+        moved || resized
+    }
+}
+
+impl SpriteSheet {
+    /// loads a sprite sheet packed as an atlas from the json exported by a texture packer or by
+    /// aseprite, in the "hash" or in the "array" format. the sprites are named after their file
+    /// names, and their ids follow the order of the file
+    ///
+    /// the aseprite tags become animations with the same name, following the direction of the
+    /// tag. the aseprite slices become sprites named after the slice, with the ids after the
+    /// ones of the frames, a slice is cut from the first frame it has a key for
+    ///
+    /// the sprites must be exported without trimming their transparent borders, because the
+    /// sheet keeps only the packed region of every sprite. in TexturePacker trimming is on by
+    /// default, set the trim mode to "None"
+    ///
+    /// ```json
+    /// {
+    ///     "frames": {
+    ///         "grass.png": { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 } },
+    ///         "house.png": { "frame": { "x": 16, "y": 0, "w": 48, "h": 32 } }
+    ///     },
+    ///     "meta": {
+    ///         "image": "atlas.png",
+    ///         "frameTags": [{ "name": "idle", "from": 0, "to": 1, "direction": "pingpong" }],
+    ///         "slices": [{
+    ///             "name": "door",
+    ///             "keys": [{ "frame": 1, "bounds": { "x": 16, "y": 16, "w": 16, "h": 16 } }]
+    ///         }]
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// fails if the atlas can't be read or parsed, if it has rotated or trimmed sprites, if the
+    /// image can't be loaded, if a sprite, a tag or a slice is outside the image, or with
+    /// `TileyError::DuplicateSpriteName` if two frames or slices have the same name
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// let sprite_sheet = SpriteSheet::from_atlas_json("./resources/atlas.json")?;
+    /// tile_grid.set_sprite_sheet(sprite_sheet);
+    /// tile_grid.draw_tile_named((3, 4), "house.png")?;
+    /// ```
+    pub fn from_atlas_json(path: impl AsRef<Path>) -> Result<Self, TileyError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        let atlas: Atlas =
+            serde_json::from_str(&content).map_err(|e| TileyError::Manifest(e.to_string()))?;
+
+        let frames: Vec<(String, Frame)> = match atlas.frames {
+            Frames::Hash(OrderedFrames(frames)) => frames,
+            Frames::Array(frames) => frames
+                .into_iter()
+                .map(|frame| (frame.filename, frame.frame))
+                .collect(),
+        };
+
+        if let Some((name, _)) = frames.iter().find(|(_, frame)| frame.rotated) {
+            return Err(TileyError::Manifest(format!(
+                "the sprite {name:?} is rotated, rotated sprites are not supported"
+            )));
+        }
+
+        if let Some((name, _)) = frames.iter().find(|(_, frame)| frame.is_trimmed()) {
+            return Err(TileyError::Manifest(format!(
+                "the sprite {name:?} is trimmed, trimmed sprites are not supported"
+            )));
+        }
+
+        let mut regions: Vec<Rect> = frames
+            .iter()
+            .map(|(_, frame)| frame.frame.to_rect())
+            .collect();
+        let mut names: Vec<String> = frames.iter().map(|(name, _)| name.clone()).collect();
+
+        for slice in &atlas.meta.slices {
+            let Some(key) = slice.keys.first() else {
+                continue;
+            };
+            let Some((_, frame)) = frames.get(key.frame) else {
+                return Err(TileyError::Manifest(format!(
+                    "the slice {:?} is in the frame {}, but there are {} frames",
+                    slice.name,
+                    key.frame,
+                    frames.len()
+                )));
+            };
+
+            // the bounds are relative to the image of the sprite
+            let region = key
+                .bounds
+                .to_rect()
+                .translate((frame.frame.x as isize, frame.frame.y as isize));
+
+            regions.push(region);
+            names.push(slice.name.clone());
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(name) = names.iter().find(|name| !seen.insert(name.as_str())) {
+            return Err(TileyError::DuplicateSpriteName(name.clone()));
+        }
+
+        let image_path = path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(&atlas.meta.image);
+        let image = ImageReader::open(&image_path)?.decode()?;
+        let mut sprite_sheet = SpriteSheet::from_atlas(image, regions)?
+            .with_source(SheetSource::new(image_path, SourceFormat::Image));
+
+        for (id, name) in names.into_iter().enumerate() {
+            sprite_sheet.name_sprite(name, (id, 0))?;
+        }
+
+        for tag in atlas.meta.frame_tags {
+            let (from, to) = (tag.from, tag.to);
+            if from > to || to >= frames.len() {
+                return Err(TileyError::Manifest(format!(
+                    "the tag {:?} goes from the frame {from} to {to}, but there are {} frames",
+                    tag.name,
+                    frames.len()
+                )));
+            }
+
+            let tag_frames: Vec<usize> = match tag.direction {
+                Direction::Forward => (from..=to).collect(),
+                Direction::Reverse => (from..=to).rev().collect(),
+                Direction::Pingpong => (from..=to).chain((from + 1..to).rev()).collect(),
+                Direction::PingpongReverse => (from..=to).rev().chain(from + 1..to).collect(),
+            };
+
+            let tag_frames = tag_frames.into_iter().map(|frame| (frame, 0)).collect();
+            sprite_sheet.add_animation(tag.name, tag_frames)?;
+        }
+
+        Ok(sprite_sheet)
+    }
+}
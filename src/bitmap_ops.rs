@@ -0,0 +1,388 @@
+//! operations on the pixels of whole bitmaps, to move, copy and transform what is already drawn
+
+use crate::{Bitmap, Color, PixelStorage, Rect, Rotation};
+
+impl<S: PixelStorage> Bitmap<S> {
+    /// moves the content of the bitmap by `dx` pixels to the right and `dy` pixels down, the
+    /// negative values move it to the left and up. the pixels uncovered on the other side are
+    /// filled with a `0xRRGGBB` color, so a scrolling background only needs its new border
+    /// redrawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 4 * 4], 4, 4);
+    /// bitmap[(1, 1)] = 0xff0000;
+    /// bitmap.scroll(2, -1, 0x0000ff);
+    /// assert_eq!(bitmap.get_pixel((3, 0)), 0xff0000);
+    /// assert_eq!(bitmap.get_pixel((0, 0)), 0x0000ff);
+    /// assert_eq!(bitmap.get_pixel((3, 3)), 0x0000ff);
+    /// ```
+    pub fn scroll(&mut self, dx: isize, dy: isize, fill_color: impl Into<Color>) {
+        let fill_color = u32::from(fill_color.into());
+        let (width, height) = (self.width, self.height);
+        let fill = self.format.pack(fill_color);
+        let pixels = self.buffer.as_pixels_mut();
+        if dx.unsigned_abs() >= width || dy.unsigned_abs() >= height {
+            pixels.fill(fill);
+            return;
+        }
+
+        // whole rows are moved at once, then every row is moved on its own
+        let shift = dy.unsigned_abs() * width;
+        match dy >= 0 {
+            true => {
+                pixels.copy_within(..pixels.len() - shift, shift);
+                pixels[..shift].fill(fill);
+            }
+            false => {
+                pixels.copy_within(shift.., 0);
+                let length = pixels.len();
+                pixels[length - shift..].fill(fill);
+            }
+        }
+
+        let shift = dx.unsigned_abs();
+        if shift == 0 {
+            return;
+        }
+        for row in pixels.chunks_exact_mut(width) {
+            match dx > 0 {
+                true => {
+                    row.copy_within(..width - shift, shift);
+                    row[..shift].fill(fill);
+                }
+                false => {
+                    row.copy_within(shift.., 0);
+                    row[width - shift..].fill(fill);
+                }
+            }
+        }
+    }
+
+    /// same as `scroll`, but the pixels that go past a border come back from the other one,
+    /// for backgrounds that repeat
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 4 * 4], 4, 4);
+    /// bitmap[(3, 3)] = 0xff0000;
+    /// bitmap.scroll_wrapping(1, 1);
+    /// assert_eq!(bitmap.get_pixel((0, 0)), 0xff0000);
+    /// ```
+    pub fn scroll_wrapping(&mut self, dx: isize, dy: isize) {
+        let (width, height) = (self.width, self.height);
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let pixels = self.buffer.as_pixels_mut();
+        let shift = dy.rem_euclid(height as isize) as usize * width;
+        pixels.rotate_right(shift);
+
+        let shift = dx.rem_euclid(width as isize) as usize;
+        if shift != 0 {
+            for row in pixels.chunks_exact_mut(width) {
+                row.rotate_right(shift);
+            }
+        }
+    }
+
+    /// copies another bitmap on this one with its top left corner at `position`, the part that
+    /// falls outside this bitmap is skipped. the pixels are converted when the two bitmaps have
+    /// different pixel formats
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// // a minimap drawn offscreen and copied on the corner of the screen
+    /// let mut minimap = Bitmap::from_vec(vec![0; 40 * 30], 40, 30);
+    /// minimap.fill(0x00ff00);
+    ///
+    /// let mut screen = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// screen.blit(&minimap, (580, -10));
+    /// assert_eq!(screen.get_pixel((599, 0)), 0x00ff00);
+    /// assert_eq!(screen.get_pixel((599, 20)), 0x000000);
+    /// ```
+    pub fn blit<T: PixelStorage>(&mut self, source: &Bitmap<T>, position: (isize, isize)) {
+        self.blit_rect(source, source.bounds(), position);
+    }
+
+    /// same as `blit`, but only the rectangle `source_rect` of the other bitmap is copied
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect};
+    ///
+    /// let mut scene = Bitmap::from_vec(vec![0; 100 * 100], 100, 100);
+    /// scene[(50, 50)] = 0xff0000;
+    ///
+    /// // picture in picture, the part of the scene around a point of interest
+    /// let mut screen = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// screen.blit_rect(&scene, Rect::new((40, 40), 20, 20), (0, 0));
+    /// assert_eq!(screen.get_pixel((10, 10)), 0xff0000);
+    /// ```
+    pub fn blit_rect<T: PixelStorage>(
+        &mut self,
+        source: &Bitmap<T>,
+        source_rect: Rect,
+        (x, y): (isize, isize),
+    ) {
+        let Some(source_rect) = source_rect.intersect(&source.bounds()) else {
+            return;
+        };
+
+        // where the rectangle lands, and the part of it inside this bitmap
+        let target = Rect::new((x, y), source_rect.width, source_rect.height);
+        let Some(visible) = target.intersect(&self.bounds()) else {
+            return;
+        };
+        let (offset_x, offset_y) = (
+            (source_rect.x + visible.x - x) as usize,
+            (source_rect.y + visible.y - y) as usize,
+        );
+
+        let (width, source_width) = (self.width, source.width);
+        let (format, source_format) = (self.format, source.format);
+        let pixels = self.buffer.as_pixels_mut();
+        let source_pixels = source.buffer.as_pixels();
+        for row in 0..visible.height {
+            let start = (visible.y as usize + row) * width + visible.x as usize;
+            let source_start = (offset_y + row) * source_width + offset_x;
+            let line = &mut pixels[start..start + visible.width];
+            let source_line = &source_pixels[source_start..source_start + visible.width];
+
+            match format == source_format {
+                true => line.copy_from_slice(source_line),
+                false => {
+                    for (pixel, &source_pixel) in line.iter_mut().zip(source_line) {
+                        *pixel = format.pack(source_format.unpack(source_pixel));
+                    }
+                }
+            }
+        }
+    }
+
+    /// a copy of a rectangle of the bitmap, with the same pixel format. the part of the
+    /// rectangle outside the bitmap is left out, so the copy can be smaller than the rectangle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect};
+    ///
+    /// let mut frame = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// frame[(310, 120)] = 0xff0000;
+    ///
+    /// // a snapshot of the area around the hit, for a transition
+    /// let snapshot = frame.crop(Rect::new((300, 100), 40, 40));
+    /// assert_eq!(snapshot.dimensions(), (40, 40));
+    /// assert_eq!(snapshot.get_pixel((10, 20)), 0xff0000);
+    ///
+    /// assert_eq!(frame.crop(Rect::new((580, 190), 40, 40)).dimensions(), (20, 10));
+    /// ```
+    pub fn crop(&self, rect: Rect) -> Bitmap {
+        let rect = rect.intersect(&self.bounds()).unwrap_or_default();
+        let mut cropped =
+            Bitmap::from_vec(vec![0; rect.width * rect.height], rect.width, rect.height)
+                .with_pixel_format(self.format);
+        cropped.blit_rect(self, rect, (0, 0));
+
+        cropped
+    }
+
+    /// a copy of the bitmap resized to `width`x`height` pixels with nearest neighbor, for
+    /// minimaps, thumbnails or to upscale a scene drawn offscreen. the copy keeps the pixel
+    /// format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut scene = Bitmap::from_vec(vec![0; 4 * 4], 4, 4);
+    /// scene[(3, 0)] = 0xff0000;
+    ///
+    /// let upscaled = scene.scaled(12, 12);
+    /// assert_eq!(upscaled.dimensions(), (12, 12));
+    /// assert_eq!(upscaled.get_pixel((9, 2)), 0xff0000);
+    /// assert_eq!(upscaled.get_pixel((8, 3)), 0x000000);
+    /// ```
+    pub fn scaled(&self, width: usize, height: usize) -> Bitmap {
+        if self.width == 0 || self.height == 0 {
+            return Bitmap::from_vec(vec![0; width * height], width, height)
+                .with_pixel_format(self.format);
+        }
+
+        let source = self.buffer.as_pixels();
+        let columns: Vec<usize> = (0..width).map(|x| x * self.width / width).collect();
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let row = &source[y * self.height / height * self.width..];
+            pixels.extend(columns.iter().map(|&x| row[x]));
+        }
+
+        Bitmap::from_vec(pixels, width, height).with_pixel_format(self.format)
+    }
+
+    /// a copy of the bitmap rotated clockwise, the width and the height are swapped by the
+    /// quarter turns. for handhelds held in portrait mode or displays mounted sideways, the game
+    /// can draw as usual and rotate the frame before showing it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rotation};
+    ///
+    /// let mut frame = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// frame[(0, 0)] = 0xff0000;
+    ///
+    /// let portrait = frame.rotated(Rotation::Cw90);
+    /// assert_eq!(portrait.dimensions(), (200, 600));
+    /// assert_eq!(portrait.get_pixel((199, 0)), 0xff0000);
+    /// ```
+    pub fn rotated(&self, rotation: Rotation) -> Bitmap {
+        let (width, height) = (self.width, self.height);
+        let (new_width, new_height) = match rotation {
+            Rotation::None | Rotation::Cw180 => (width, height),
+            Rotation::Cw90 | Rotation::Cw270 => (height, width),
+        };
+
+        let source = self.buffer.as_pixels();
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..new_height {
+            pixels.extend((0..new_width).map(|x| {
+                let (source_x, source_y) = match rotation {
+                    Rotation::None => (x, y),
+                    Rotation::Cw90 => (y, height - 1 - x),
+                    Rotation::Cw180 => (width - 1 - x, height - 1 - y),
+                    Rotation::Cw270 => (width - 1 - y, x),
+                };
+                source[source_y * width + source_x]
+            }));
+        }
+
+        Bitmap::from_vec(pixels, new_width, new_height).with_pixel_format(self.format)
+    }
+
+    /// same as `rotated`, but the bitmap is rotated in place. square bitmaps are rotated without
+    /// allocating a copy, the other ones are rotated through a copy by the quarter turns, which
+    /// swap their width and height
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rotation};
+    ///
+    /// let mut frame = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// frame[(0, 0)] = 0xff0000;
+    ///
+    /// frame.rotate(Rotation::Cw90);
+    /// assert_eq!(frame.dimensions(), (200, 600));
+    /// assert_eq!(frame.get_pixel((199, 0)), 0xff0000);
+    /// ```
+    pub fn rotate(&mut self, rotation: Rotation) {
+        let size = self.width;
+        if let Rotation::Cw90 | Rotation::Cw270 = rotation {
+            if self.width != self.height {
+                let rotated = self.rotated(rotation);
+                let pixels = self.buffer.as_pixels_mut();
+                pixels.copy_from_slice(rotated.buffer.as_pixels());
+                (self.width, self.height) = (self.height, self.width);
+                return;
+            }
+
+            // a quarter turn is a transpose followed by a flip
+            let pixels = self.buffer.as_pixels_mut();
+            for y in 0..size {
+                for x in y + 1..size {
+                    pixels.swap(y * size + x, x * size + y);
+                }
+            }
+        }
+
+        let pixels = self.buffer.as_pixels_mut();
+        match rotation {
+            Rotation::None => {}
+            Rotation::Cw90 => pixels.chunks_exact_mut(size).for_each(|row| row.reverse()),
+            Rotation::Cw180 => pixels.reverse(),
+            Rotation::Cw270 => {
+                for y in 0..size / 2 {
+                    let (top, bottom) = pixels.split_at_mut((size - 1 - y) * size);
+                    top[y * size..(y + 1) * size].swap_with_slice(&mut bottom[..size]);
+                }
+            }
+        }
+    }
+
+    /// replaces every pixel by what `map` returns for it, given its coordinates and its
+    /// `0xRRGGBB` color, for one off effects like noise, tints or masks. the colors are
+    /// converted from and to the pixel format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut frame = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// frame.fill(0xffffff);
+    ///
+    /// // darken every other row, like an old screen
+    /// frame.map_pixels(|_, y, color| match y % 2 {
+    ///     0 => color,
+    ///     _ => color >> 1 & 0x7f7f7f,
+    /// });
+    /// assert_eq!(frame.get_pixel((0, 0)), 0xffffff);
+    /// assert_eq!(frame.get_pixel((0, 1)), 0x7f7f7f);
+    /// ```
+    pub fn map_pixels(&mut self, mut map: impl FnMut(usize, usize, u32) -> u32) {
+        let (width, format) = (self.width, self.format);
+        if width == 0 {
+            return;
+        }
+
+        for (y, row) in self
+            .buffer
+            .as_pixels_mut()
+            .chunks_exact_mut(width)
+            .enumerate()
+        {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = format.pack(map(x, y, format.unpack(*pixel)) & 0xffffff);
+            }
+        }
+    }
+
+    /// calls `f` on every pixel, with its coordinates and its `0xRRGGBB` color, row by row
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// bitmap[(10, 20)] = 0xff0000;
+    ///
+    /// let mut red = Vec::new();
+    /// bitmap.for_each_pixel(|x, y, color| {
+    ///     if color == 0xff0000 {
+    ///         red.push((x, y));
+    ///     }
+    /// });
+    /// assert_eq!(red, [(10, 20)]);
+    /// ```
+    pub fn for_each_pixel(&self, mut f: impl FnMut(usize, usize, u32)) {
+        for ((x, y), color) in self.pixels() {
+            f(x, y, color);
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! helpers for the `0xRRGGBB` colors used by the whole crate, to build, mix and convert them
+//! without writing the bit math every time
+//!
+//! # Examples
+//!
+//! ```
+//! use tiley::color;
+//!
+//! let sky = color::rgb(0x40, 0x80, 0xff);
+//! let dusk = color::lerp(sky, color::ORANGE, 0.5);
+//! let night = color::darken(sky, 0.75);
+//! assert_eq!(color::channels(night), (0x10, 0x20, 0x40));
+//! ```
+
+use std::{fmt, str::FromStr};
+
+use crate::{PixelFormat, TileyError};
+
+/// a color, from its red, green and blue channels. every drawing method takes an
+/// `impl Into<Color>`, so it can be given as a `Color`, as a `0xRRGGBB` number or as a tuple
+/// of channels, and it's packed in the pixel format of the bitmap when it's drawn
+///
+/// # Examples
+///
+/// ```
+/// use tiley::{Bitmap, Color, PixelFormat};
+///
+/// let grass: Color = "#4a8c2a".parse()?;
+/// assert_eq!(grass, Color::rgb(0x4a, 0x8c, 0x2a));
+/// assert_eq!(u32::from(grass), 0x4a8c2a);
+/// assert_eq!(grass.pack(PixelFormat::Rgba), 0x4a8c2aff);
+///
+/// let mut bitmap = Bitmap::from_vec(vec![0; 4 * 4], 4, 4);
+/// bitmap.fill(grass);
+/// bitmap.fill((0x4a, 0x8c, 0x2a));
+/// bitmap.fill(0x4a8c2a);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+pub const BLACK: u32 = 0x000000;
+pub const WHITE: u32 = 0xffffff;
+pub const GRAY: u32 = 0x808080;
+pub const RED: u32 = 0xff0000;
+pub const GREEN: u32 = 0x00ff00;
+pub const BLUE: u32 = 0x0000ff;
+pub const YELLOW: u32 = 0xffff00;
+pub const CYAN: u32 = 0x00ffff;
+pub const MAGENTA: u32 = 0xff00ff;
+pub const ORANGE: u32 = 0xff8000;
+
+impl Color {
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    /// parses a color written as `#RRGGBB`, `#RGB` or `0xRRGGBB`, the `#` can be left out
+    ///
+    /// # Errors
+    ///
+    /// fails if the text is not a color in one of these forms
+    pub fn from_hex(hex: &str) -> Result<Self, TileyError> {
+        let digits = hex
+            .strip_prefix('#')
+            .or_else(|| hex.strip_prefix("0x"))
+            .unwrap_or(hex);
+        let invalid = || TileyError::InvalidColor(hex.to_string());
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let value = u32::from_str_radix(digits, 16).map_err(|_| invalid())?;
+        match digits.len() {
+            6 => Ok(Color::from(value)),
+            // every digit is repeated, #f80 is #ff8800
+            3 => {
+                let channel = |shift: u32| ((value >> shift) & 0xf) as u8 * 0x11;
+                Ok(Color::rgb(channel(8), channel(4), channel(0)))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// the color packed in a pixel format, as it's stored in a bitmap
+    pub fn pack(self, format: PixelFormat) -> u32 {
+        format.pack(self.into())
+    }
+
+    /// the color of a pixel packed in a pixel format
+    pub fn unpack(pixel: u32, format: PixelFormat) -> Self {
+        format.unpack(pixel).into()
+    }
+}
+
+impl From<u32> for Color {
+    /// a `0xRRGGBB` color, the highest byte is ignored
+    fn from(color: u32) -> Self {
+        let [_, r, g, b] = color.to_be_bytes();
+        Color { r, g, b }
+    }
+}
+
+impl From<Color> for u32 {
+    /// the color as `0xRRGGBB`
+    fn from(Color { r, g, b }: Color) -> Self {
+        u32::from_be_bytes([0, r, g, b])
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self {
+        Color { r, g, b }
+    }
+}
+
+impl From<[u8; 3]> for Color {
+    fn from([r, g, b]: [u8; 3]) -> Self {
+        Color { r, g, b }
+    }
+}
+
+impl FromStr for Color {
+    type Err = TileyError;
+
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        Color::from_hex(hex)
+    }
+}
+
+impl fmt::Display for Color {
+    /// formats the color as `#RRGGBB`, that can be parsed back
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// a color from its red, green and blue channels
+pub fn rgb(r: u8, g: u8, b: u8) -> u32 {
+    u32::from_be_bytes([0, r, g, b])
+}
+
+/// the red, green and blue channels of a color
+pub fn channels(color: u32) -> (u8, u8, u8) {
+    let [_, r, g, b] = color.to_be_bytes();
+    (r, g, b)
+}
+
+/// the color between `a` and `b`, with `t` from 0.0 for `a` to 1.0 for `b`
+///
+/// # Examples
+///
+/// ```
+/// use tiley::color;
+///
+/// assert_eq!(color::lerp(color::BLACK, color::WHITE, 0.5), 0x808080);
+/// assert_eq!(color::lerp(color::RED, color::BLUE, 1.0), color::BLUE);
+/// ```
+pub fn lerp(a: u32, b: u32, t: f32) -> u32 {
+    let t = t.clamp(0.0, 1.0);
+    let ((a_r, a_g, a_b), (b_r, b_g, b_b)) = (channels(a), channels(b));
+    let channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+    rgb(channel(a_r, b_r), channel(a_g, b_g), channel(a_b, b_b))
+}
+
+/// a lighter color, mixed with white by `amount` from 0.0 to 1.0
+pub fn brighten(color: u32, amount: f32) -> u32 {
+    lerp(color, WHITE, amount)
+}
+
+/// a darker color, mixed with black by `amount` from 0.0 to 1.0
+pub fn darken(color: u32, amount: f32) -> u32 {
+    lerp(color, BLACK, amount)
+}
+
+/// the hue, saturation and value of a color. the hue is in degrees from 0.0 to 360.0, with red
+/// at 0.0, and the saturation and the value go from 0.0 to 1.0
+///
+/// # Examples
+///
+/// ```
+/// use tiley::color;
+///
+/// assert_eq!(color::rgb_to_hsv(color::BLUE), (240.0, 1.0, 1.0));
+/// assert_eq!(color::rgb_to_hsv(color::GRAY).1, 0.0);
+/// ```
+pub fn rgb_to_hsv(color: u32) -> (f32, f32, f32) {
+    let (r, g, b) = channels(color);
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let delta = max - min;
+
+    let hue = match delta {
+        0.0 => 0.0,
+        _ if max == r => 60.0 * ((g - b) / delta).rem_euclid(6.0),
+        _ if max == g => 60.0 * ((b - r) / delta + 2.0),
+        _ => 60.0 * ((r - g) / delta + 4.0),
+    };
+    let saturation = match max {
+        0.0 => 0.0,
+        _ => delta / max,
+    };
+
+    (hue, saturation, max)
+}
+
+/// the color with a hue in degrees, wrapped around 360.0, and a saturation and a value from
+/// 0.0 to 1.0. it's the inverse of `rgb_to_hsv`
+///
+/// # Examples
+///
+/// ```
+/// use tiley::color;
+///
+/// // the same blue rotated to yellow on the color wheel
+/// let (hue, saturation, value) = color::rgb_to_hsv(color::BLUE);
+/// assert_eq!(color::hsv_to_rgb(hue + 180.0, saturation, value), color::YELLOW);
+/// ```
+pub fn hsv_to_rgb(hue: f32, saturation: f32, value: f32) -> u32 {
+    let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+    let hue = hue.rem_euclid(360.0) / 60.0;
+
+    let chroma = value * saturation;
+    let x = chroma * (1.0 - (hue.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hue as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let min = value - chroma;
+    let channel = |c: f32| ((c + min) * 255.0).round() as u8;
+    rgb(channel(r), channel(g), channel(b))
+}
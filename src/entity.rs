@@ -0,0 +1,173 @@
+//! sprites that move freely over the tiles, like characters and projectiles
+
+use crate::{Anchor, PixelStorage, SpriteId, TileGrid};
+
+/// a sprite over the tiles, its position is in tiles and can be fractional for smooth movement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Entity {
+    /// position of the anchor of the sprite, in tiles
+    pub position: (f32, f32),
+    pub sprite_id: usize,
+    /// the point of the sprite that is on the position, the top left corner by default
+    pub anchor: Anchor,
+}
+
+/// handle to an entity of an entity layer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityId(usize);
+
+/// the sprites drawn over the tile layer every frame. they are drawn from the top to the bottom
+/// of the grid, so the entities lower on the screen are in front of the ones behind them
+///
+/// # Examples
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use tiley::{Bitmap, EntityLayer, SpriteSheet, TileGrid};
+///
+/// // green grass, a red player and a blue enemy
+/// let sheet = RgbaImage::from_fn(24, 8, |x, _| match x / 8 {
+///     0 => Rgba([0, 255, 0, 255]),
+///     1 => Rgba([255, 0, 0, 255]),
+///     _ => Rgba([0, 0, 255, 255]),
+/// });
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+///
+/// let mut entities = EntityLayer::new();
+/// let enemy = entities.add((3.5, 4.25), 2);
+/// let player = entities.add((3.0, 4.0), 1);
+///
+/// // every frame
+/// entities.get_mut(player).unwrap().position.0 += 0.125;
+/// tile_grid.fill_all(0);
+/// entities.draw(&mut tile_grid);
+///
+/// // the player moved by a virtual pixel, an eighth of a tile
+/// assert_eq!(tile_grid.bitmap().get_pixel((24, 32)), 0x00ff00);
+/// assert_eq!(tile_grid.bitmap().get_pixel((25, 32)), 0xff0000);
+/// // the enemy is lower, so it's in front of the player even if it was added first
+/// assert_eq!(tile_grid.bitmap().get_pixel((29, 35)), 0x0000ff);
+///
+/// entities.remove(enemy);
+/// assert_eq!(entities.len(), 1);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EntityLayer {
+    /// the entities by id, removed entities leave their slot empty so the other ids stay valid
+    entities: Vec<Option<Entity>>,
+}
+
+impl EntityLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// adds an entity with its top left corner at a position in tiles
+    pub fn add(&mut self, position: (f32, f32), sprite_id: impl SpriteId) -> EntityId {
+        self.add_anchored(position, sprite_id, Anchor::TopLeft)
+    }
+
+    /// adds an entity with its anchor at a position in tiles, with the bottom center the
+    /// characters are sorted by their feet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Anchor, EntityLayer};
+    ///
+    /// let mut entities = EntityLayer::new();
+    /// // standing on the bottom of the tile (3, 4), in the middle of it
+    /// let player = entities.add_anchored((3.5, 5.0), 5, Anchor::BottomCenter);
+    /// ```
+    pub fn add_anchored(
+        &mut self,
+        position: (f32, f32),
+        sprite_id: impl SpriteId,
+        anchor: Anchor,
+    ) -> EntityId {
+        self.entities.push(Some(Entity {
+            position,
+            sprite_id: sprite_id.id(),
+            anchor,
+        }));
+
+        EntityId(self.entities.len() - 1)
+    }
+
+    /// removes an entity, returning it if it was still in the layer
+    pub fn remove(&mut self, entity_id: EntityId) -> Option<Entity> {
+        self.entities.get_mut(entity_id.0)?.take()
+    }
+
+    pub fn get(&self, entity_id: EntityId) -> Option<&Entity> {
+        self.entities.get(entity_id.0)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, entity_id: EntityId) -> Option<&mut Entity> {
+        self.entities.get_mut(entity_id.0)?.as_mut()
+    }
+
+    /// number of entities in the layer
+    pub fn len(&self) -> usize {
+        self.entities.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// iterates over the entities with their ids, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = (EntityId, &Entity)> {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter_map(|(id, entity)| Some((EntityId(id), entity.as_ref()?)))
+    }
+
+    /// removes every entity. the slots are kept empty, so the ids of the removed entities are
+    /// not given to the entities added later
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::EntityLayer;
+    ///
+    /// let mut entities = EntityLayer::new();
+    /// let player = entities.add((3.0, 4.0), 5);
+    ///
+    /// entities.clear();
+    /// let enemy = entities.add((6.0, 4.0), 6);
+    /// assert!(entities.get(player).is_none());
+    /// assert_eq!(entities.get(enemy).unwrap().sprite_id, 6);
+    /// ```
+    pub fn clear(&mut self) {
+        self.entities.fill(None);
+    }
+
+    /// draws the entities on a tile grid, sorted by the y coordinate of their anchor, the
+    /// entities with the same y are drawn in the order they were added. the positions are
+    /// rounded to the nearest virtual pixel and the entities outside the grid are clipped
+    ///
+    /// # Panics
+    ///
+    /// panics if the sprite of an entity is outside the sprite sheet, in debug builds or with
+    /// the `bounds-checks` feature
+    pub fn draw<S: PixelStorage>(&self, tile_grid: &mut TileGrid<S>) {
+        let mut entities: Vec<&Entity> = self.entities.iter().flatten().collect();
+        entities.sort_by(|a, b| a.position.1.total_cmp(&b.position.1));
+
+        let (tile_width, tile_height) = tile_grid.tile_dimensions();
+        for entity in entities {
+            let (x, y) = entity.position;
+            let vpixel = (
+                (x * tile_width as f32).round() as isize,
+                (y * tile_height as f32).round() as isize,
+            );
+
+            tile_grid.draw_sprite_anchored(vpixel, entity.sprite_id, entity.anchor);
+        }
+    }
+}
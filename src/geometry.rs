@@ -0,0 +1,231 @@
+//! small geometry types used for positions and clipping
+
+/// a point on a grid, the coordinates are signed so a point can also be above or to the left of
+/// the grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+/// an axis aligned rectangle on a grid, from its top left corner with a width and a height
+///
+/// # Examples
+/// ```
+/// use tiley::{Point, Rect};
+///
+/// let grid = Rect::new((0, 0), 20, 10);
+/// let sprite = Rect::new((-4, 6), 8, 8);
+///
+/// assert_eq!(sprite.intersect(&grid), Some(Rect::new((0, 6), 4, 4)));
+/// assert!(grid.contains(Point::new(19, 9)));
+/// assert!(!grid.contains(Point::new(20, 9)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rect {
+    pub x: isize,
+    pub y: isize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Point {
+    pub fn new(x: isize, y: isize) -> Self {
+        Self { x, y }
+    }
+
+    /// the coordinates of the point, if they are both non negative
+    pub fn to_unsigned(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    /// coordinates that don't fit in an `isize` are saturated
+    fn from((x, y): (usize, usize)) -> Self {
+        Self {
+            x: x.min(isize::MAX as usize) as isize,
+            y: y.min(isize::MAX as usize) as isize,
+        }
+    }
+}
+
+impl Rect {
+    pub fn new((x, y): (isize, isize), width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    /// x coordinate right after the right border of the rectangle
+    pub fn right(&self) -> isize {
+        self.x.saturating_add_unsigned(self.width)
+    }
+
+    /// y coordinate right after the bottom border of the rectangle
+    pub fn bottom(&self) -> isize {
+        self.y.saturating_add_unsigned(self.height)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn contains(&self, point: impl Into<Point>) -> bool {
+        let Point { x, y } = point.into();
+
+        self.x <= x && x < self.right() && self.y <= y && y < self.bottom()
+    }
+
+    /// the part of the rectangle that is also inside the other rectangle, or `None` if they
+    /// don't overlap
+    pub fn intersect(&self, other: &Rect) -> Option<Rect> {
+        let (left, top) = (self.x.max(other.x), self.y.max(other.y));
+        let (right, bottom) = (
+            self.right().min(other.right()),
+            self.bottom().min(other.bottom()),
+        );
+
+        (left < right && top < bottom).then(|| Rect {
+            x: left,
+            y: top,
+            width: right.abs_diff(left),
+            height: bottom.abs_diff(top),
+        })
+    }
+
+    /// same as `intersect`, but returns an empty rectangle inside `bounds` if they don't overlap
+    pub fn clamp_to(&self, bounds: &Rect) -> Rect {
+        self.intersect(bounds).unwrap_or(Rect {
+            x: self.x.clamp(bounds.x, bounds.right()),
+            y: self.y.clamp(bounds.y, bounds.bottom()),
+            width: 0,
+            height: 0,
+        })
+    }
+
+    /// moves the rectangle by an offset
+    pub fn translate(&self, (dx, dy): (isize, isize)) -> Rect {
+        Rect {
+            x: self.x.saturating_add(dx),
+            y: self.y.saturating_add(dy),
+            ..*self
+        }
+    }
+}
+
+/// position of a tile in a tile grid, in tiles
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TilePos {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// position of a virtual pixel in the pixel grid under a tile grid, in virtual pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct VPixelPos {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// position of a pixel in the bitmap, in screen pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ScreenPos {
+    pub x: usize,
+    pub y: usize,
+}
+
+macro_rules! impl_position {
+    ($($position:ident),*) => {
+        $(
+            impl $position {
+                pub fn new(x: usize, y: usize) -> Self {
+                    Self { x, y }
+                }
+            }
+
+            impl From<(usize, usize)> for $position {
+                fn from((x, y): (usize, usize)) -> Self {
+                    Self { x, y }
+                }
+            }
+
+            impl From<$position> for (usize, usize) {
+                fn from(position: $position) -> Self {
+                    (position.x, position.y)
+                }
+            }
+        )*
+    };
+}
+
+impl_position!(TilePos, VPixelPos, ScreenPos);
+
+/// the point of a sprite its position refers to, so characters can be placed by their feet or
+/// effects by their center instead of by the top left corner
+///
+/// # Examples
+/// ```
+/// use tiley::Anchor;
+///
+/// assert_eq!(Anchor::TopLeft.offset((16, 16)), (0, 0));
+/// assert_eq!(Anchor::BottomCenter.offset((16, 16)), (8, 16));
+/// assert_eq!(Anchor::Custom(0.25, 0.5).offset((16, 16)), (4, 8));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    TopCenter,
+    TopRight,
+    CenterLeft,
+    Center,
+    CenterRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+    /// a point given as a fraction of the width and of the height, from the top left corner
+    Custom(f32, f32),
+}
+
+impl Anchor {
+    /// the anchor as a fraction of the width and of the height, from the top left corner
+    pub fn fraction(self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::TopCenter => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::CenterLeft => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::CenterRight => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::BottomCenter => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+            Anchor::Custom(x, y) => (x, y),
+        }
+    }
+
+    /// distance of the anchor from the top left corner of a `width`x`height` sprite, rounded
+    /// to the nearest pixel
+    pub fn offset(self, (width, height): (usize, usize)) -> (isize, isize) {
+        let (x, y) = self.fraction();
+
+        (
+            (x * width as f32).round() as isize,
+            (y * height as f32).round() as isize,
+        )
+    }
+}
@@ -0,0 +1,169 @@
+//! sprites stored as indices in a palette, drawn with the palette of the tile grid so the colors
+//! of the whole screen can be swapped or faded at once
+
+use image::{DynamicImage, GenericImageView};
+
+use crate::TileyError;
+
+/// a sheet of sprites stored as one byte per pixel, the index of its color in a palette
+/// of 256 colors. the index 0 is reserved for the transparent pixels, they are never drawn, so
+/// the sprites can use the indices from 1 to 255
+///
+/// the sprites are drawn with `TileGrid::draw_tile_indexed`, taking the colors from the palette
+/// of the tile grid at the moment they are drawn
+#[derive(Debug, Clone)]
+pub struct IndexedSpriteSheet {
+    /// the palette index of every pixel of the image, row by row
+    indices: Vec<u8>,
+    width: usize,
+    height: usize,
+    sprite_width: usize,
+    sprite_height: usize,
+}
+
+impl IndexedSpriteSheet {
+    /// converts an image to palette indices, the opaque pixels get the index of their color in
+    /// the palette and the pixels with less than half alpha get the transparent index 0. the
+    /// palette has `0xRRGGBB` colors and its first entry is skipped, like the transparent index
+    ///
+    /// # Errors
+    ///
+    /// fails if the image dimensions are not a multiple of `sprite_size`, or if the color of an
+    /// opaque pixel is not in the palette
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::IndexedSpriteSheet;
+    ///
+    /// let mut palette = [0; 256];
+    /// palette[1] = 0x306230;
+    /// palette[2] = 0x8bac0f;
+    ///
+    /// let image = RgbaImage::from_pixel(16, 8, Rgba([0x30, 0x62, 0x30, 255]));
+    /// let sprite_sheet = IndexedSpriteSheet::from_image(image, 8, &palette)?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 2);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_image(
+        image: impl Into<DynamicImage>,
+        sprite_size: usize,
+        palette: &[u32; 256],
+    ) -> Result<Self, TileyError> {
+        Self::from_image_with_dimensions(image, (sprite_size, sprite_size), palette)
+    }
+
+    /// same as `from_image`, but for rectangular sprites, `sprite_width` pixels wide and
+    /// `sprite_height` pixels tall, like the ones of a tile grid with rectangular tiles
+    ///
+    /// # Errors
+    ///
+    /// fails if the image dimensions are not a multiple of the sprite dimensions, or if the color
+    /// of an opaque pixel is not in the palette
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, IndexedSpriteSheet, SpriteSheet, TileGrid};
+    ///
+    /// let mut palette = [0; 256];
+    /// palette[1] = 0xff0000;
+    /// palette[2] = 0x0000ff;
+    ///
+    /// // a half height sprite, red on the left and blue on the right
+    /// let image = RgbaImage::from_fn(16, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([255, 0, 0, 255]),
+    ///     _ => Rgba([0, 0, 255, 255]),
+    /// });
+    /// let indexed = IndexedSpriteSheet::from_image_with_dimensions(image, (16, 8), &palette)?;
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image_with_dimensions(image::RgbImage::new(16, 8), (16, 8), 0, 0)?;
+    /// let mut tile_grid = TileGrid::with_tile_dimensions(bitmap, 10, 10, (16, 8), sprite_sheet)?;
+    /// tile_grid.set_palette(palette);
+    ///
+    /// // the sprite has the dimensions of the tiles, so it's not stretched
+    /// tile_grid.draw_tile_indexed((0, 0), &indexed, 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7, 7)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((8, 7)), 0x0000ff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_image_with_dimensions(
+        image: impl Into<DynamicImage>,
+        (sprite_width, sprite_height): (usize, usize),
+        palette: &[u32; 256],
+    ) -> Result<Self, TileyError> {
+        let image = image.into();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        if sprite_width == 0
+            || sprite_height == 0
+            || width % sprite_width != 0
+            || height % sprite_height != 0
+        {
+            return Err(TileyError::BadDimensions(format!(
+                "an image of {width}x{height} pixels can't be sliced in sprites of {sprite_width}x{sprite_height}"
+            )));
+        }
+
+        let indices = image
+            .pixels()
+            .map(|(x, y, pixel)| {
+                let [r, g, b, a] = pixel.0;
+                if a < 128 {
+                    return Ok(0);
+                }
+
+                let color = u32::from_be_bytes([0, r, g, b]);
+                palette[1..]
+                    .iter()
+                    .position(|&entry| entry & 0xffffff == color)
+                    .map(|index| index as u8 + 1)
+                    .ok_or(TileyError::ColorNotInPalette {
+                        color,
+                        position: (x as usize, y as usize),
+                    })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(IndexedSpriteSheet {
+            indices,
+            width,
+            height,
+            sprite_width,
+            sprite_height,
+        })
+    }
+
+    /// side length of the sprites, in pixels, for rectangular sprites it's the width
+    pub fn sprite_size(&self) -> usize {
+        self.sprite_width
+    }
+
+    /// width of the sprites, in pixels
+    pub fn sprite_width(&self) -> usize {
+        self.sprite_width
+    }
+
+    /// height of the sprites, in pixels
+    pub fn sprite_height(&self) -> usize {
+        self.sprite_height
+    }
+
+    /// number of sprites in the sheet, they are numbered row by row from the top left one
+    pub fn sprite_count(&self) -> usize {
+        (self.width / self.sprite_width) * (self.height / self.sprite_height)
+    }
+
+    /// palette index of the pixel at `(x, y)` in the sprite with the given id
+    pub(crate) fn index(&self, sprite_id: usize, (x, y): (usize, usize)) -> u8 {
+        let columns = self.width / self.sprite_width;
+        let (left, top) = (
+            sprite_id % columns * self.sprite_width,
+            sprite_id / columns * self.sprite_height,
+        );
+
+        self.indices[(top + y) * self.width + left + x]
+    }
+}
@@ -1,284 +1,5287 @@
-use std::path::Path;
+use std::{
+    collections::HashMap,
+    fmt, fs,
+    io::{self, BufReader, Read, Seek},
+    ops::{BitOr, Index, IndexMut},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
 
-use image::{GenericImageView, ImageReader, RgbImage, SubImage};
+use image::{DynamicImage, GenericImageView, ImageError, ImageReader, Rgba, RgbaImage, SubImage};
+
+/// asserts the validity of user provided arguments, like `debug_assert!` but also in release
+/// builds when the `bounds-checks` feature is enabled. it's defined before the modules so
+/// they can use it too
+macro_rules! check {
+    ($($arg:tt)*) => {
+        if cfg!(feature = "bounds-checks") {
+            assert!($($arg)*);
+        } else {
+            debug_assert!($($arg)*);
+        }
+    };
+}
+
+#[cfg(feature = "aseprite")]
+mod aseprite;
+#[cfg(feature = "manifest")]
+mod atlas;
+mod bitmap_ops;
+pub mod color;
+mod entity;
+mod geometry;
+mod indexed;
+#[cfg(feature = "manifest")]
+mod manifest;
+mod packer;
+mod palette;
+mod post;
+mod shapes;
+
+pub use color::Color;
+pub use entity::{Entity, EntityId, EntityLayer};
+pub use geometry::{Anchor, Point, Rect, ScreenPos, TilePos, VPixelPos};
+pub use indexed::IndexedSpriteSheet;
+pub use palette::{PaletteCycle, PaletteMap};
+pub use post::{
+    Adjust, AmbientTint, ColorLut, CrtFilter, PostContext, PostEffect, PostPipeline, Vignette,
+};
+pub use shapes::{DitherPattern, Gradient, HighlightStyle, PathStyle};
+
+/// errors that can happen while building the tiley abstractions
+#[derive(Debug)]
+pub enum TileyError {
+    /// the sprite sheet file could not be opened or read
+    Io(io::Error),
+    /// the sprite sheet file could not be decoded as an image
+    Decode(ImageError),
+    /// the requested dimensions can't be used, the message explains why
+    BadDimensions(String),
+    /// the tile coordinates are outside the tile grid
+    TileOutOfBounds {
+        position: (usize, usize),
+        dimensions: (usize, usize),
+    },
+    /// the sprite id maps to a sprite outside the sprite sheet
+    SpriteOutOfBounds { sprite_id: usize },
+    /// the tile grid builder was finished without setting a sprite sheet
+    MissingSpriteSheet,
+    /// no sprite or animation in the sprite sheet has the given name
+    UnknownSpriteName(String),
+    /// more than one sprite of a sheet being loaded has the given name
+    DuplicateSpriteName(String),
+    /// the sprite sheet manifest or atlas file is not valid, the message explains why
+    Manifest(String),
+    /// the sprite sheet can't be reloaded because it was not loaded from a file
+    NoSourceFile,
+    /// the `0xRRGGBB` color of the pixel at the position in the image is not in the palette
+    ColorNotInPalette {
+        color: u32,
+        position: (usize, usize),
+    },
+    /// the text is not a hexadecimal color
+    InvalidColor(String),
+}
+
+impl fmt::Display for TileyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TileyError::Io(err) => write!(f, "io error: {err}"),
+            TileyError::Decode(err) => write!(f, "decode error: {err}"),
+            TileyError::BadDimensions(msg) => write!(f, "bad dimensions: {msg}"),
+            TileyError::TileOutOfBounds {
+                position: (x, y),
+                dimensions: (width, height),
+            } => {
+                write!(f, "tile ({x}, {y}) is out of bounds:")?;
+                if x >= width {
+                    write!(f, " x is not in 0..{width}")?;
+                }
+                if x >= width && y >= height {
+                    write!(f, " and")?;
+                }
+                if y >= height {
+                    write!(f, " y is not in 0..{height}")?;
+                }
+                Ok(())
+            }
+            TileyError::SpriteOutOfBounds { sprite_id } => {
+                write!(f, "sprite {sprite_id} is outside the sprite sheet")
+            }
+            TileyError::MissingSpriteSheet => write!(f, "no sprite sheet was set"),
+            TileyError::UnknownSpriteName(name) => write!(f, "no sprite is named {name:?}"),
+            TileyError::DuplicateSpriteName(name) => {
+                write!(f, "more than one sprite is named {name:?}")
+            }
+            TileyError::Manifest(msg) => write!(f, "invalid manifest: {msg}"),
+            TileyError::NoSourceFile => write!(f, "the sprite sheet was not loaded from a file"),
+            TileyError::ColorNotInPalette {
+                color,
+                position: (x, y),
+            } => write!(
+                f,
+                "the color {color:#08x} of the pixel ({x}, {y}) is not in the palette"
+            ),
+            TileyError::InvalidColor(text) => write!(f, "{text:?} is not a hexadecimal color"),
+        }
+    }
+}
+
+impl std::error::Error for TileyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TileyError::Io(err) => Some(err),
+            TileyError::Decode(err) => Some(err),
+            TileyError::BadDimensions(_)
+            | TileyError::TileOutOfBounds { .. }
+            | TileyError::SpriteOutOfBounds { .. }
+            | TileyError::MissingSpriteSheet
+            | TileyError::UnknownSpriteName(_)
+            | TileyError::DuplicateSpriteName(_)
+            | TileyError::Manifest(_)
+            | TileyError::NoSourceFile
+            | TileyError::ColorNotInPalette { .. }
+            | TileyError::InvalidColor(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for TileyError {
+    fn from(err: io::Error) -> Self {
+        TileyError::Io(err)
+    }
+}
+
+impl From<ImageError> for TileyError {
+    fn from(err: ImageError) -> Self {
+        match err {
+            ImageError::IoError(err) => TileyError::Io(err),
+            err => TileyError::Decode(err),
+        }
+    }
+}
+
+/// memory that can back a bitmap, a contiguous run of pixels stored row by row.
+/// implement it to draw on custom buffers, like memory mapped framebuffers
+///
+/// # Examples
+/// ```
+/// use tiley::{Bitmap, PixelStorage};
+///
+/// struct Framebuffer([u32; 4 * 3]);
+///
+/// impl PixelStorage for Framebuffer {
+///     fn as_pixels(&self) -> &[u32] {
+///         &self.0
+///     }
+///
+///     fn as_pixels_mut(&mut self) -> &mut [u32] {
+///         &mut self.0
+///     }
+/// }
+///
+/// let mut bitmap = Bitmap::from_storage(Framebuffer([0; 4 * 3]), 4, 3);
+/// bitmap.fill(0xffffff);
+/// ```
+pub trait PixelStorage {
+    /// the pixels in the storage, row by row
+    fn as_pixels(&self) -> &[u32];
+
+    /// mutable access to the pixels in the storage, row by row
+    fn as_pixels_mut(&mut self) -> &mut [u32];
+}
+
+impl PixelStorage for Vec<u32> {
+    fn as_pixels(&self) -> &[u32] {
+        self
+    }
+
+    fn as_pixels_mut(&mut self) -> &mut [u32] {
+        self
+    }
+}
+
+impl PixelStorage for Box<[u32]> {
+    fn as_pixels(&self) -> &[u32] {
+        self
+    }
+
+    fn as_pixels_mut(&mut self) -> &mut [u32] {
+        self
+    }
+}
+
+impl PixelStorage for &mut [u32] {
+    fn as_pixels(&self) -> &[u32] {
+        self
+    }
+
+    fn as_pixels_mut(&mut self) -> &mut [u32] {
+        self
+    }
+}
 
 /// abstraction over the bitmap buffer of the window, to add a width and height in screen pixels to
-/// the window
-pub struct Bitmap {
-    buffer: Vec<u32>,
+/// the window.
+/// the buffer is usually an owned `Vec<u32>`, but can be any `PixelStorage`, like the `&mut [u32]`
+/// frame handed out by some windowing crates
+pub struct Bitmap<S = Vec<u32>> {
+    buffer: S,
     width: usize,
     height: usize,
+    format: PixelFormat,
+}
+
+/// how a color is packed in the `u32` pixels of a bitmap, from the most significant byte to the
+/// least significant one. the colors passed to the drawing functions are always `0xRRGGBB`, they
+/// are packed in the format of the bitmap when they are written, with an opaque alpha
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PixelFormat {
+    /// `0x00RRGGBB`, the format used by minifb
+    #[default]
+    ZeroRgb,
+    /// `0xAARRGGBB`
+    Argb,
+    /// `0xAABBGGRR`, the byte order of a canvas `ImageData` read as little endian `u32`s
+    Abgr,
+    /// `0xRRGGBBAA`
+    Rgba,
+}
+
+impl PixelFormat {
+    /// packs a `0xRRGGBB` color in this format
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::PixelFormat;
+    ///
+    /// assert_eq!(PixelFormat::ZeroRgb.pack(0x112233), 0x00112233);
+    /// assert_eq!(PixelFormat::Argb.pack(0x112233), 0xff112233);
+    /// assert_eq!(PixelFormat::Abgr.pack(0x112233), 0xff332211);
+    /// assert_eq!(PixelFormat::Rgba.pack(0x112233), 0x112233ff);
+    /// ```
+    pub fn pack(self, color: u32) -> u32 {
+        let [_, r, g, b] = color.to_be_bytes();
+
+        match self {
+            PixelFormat::ZeroRgb => u32::from_be_bytes([0, r, g, b]),
+            PixelFormat::Argb => u32::from_be_bytes([0xff, r, g, b]),
+            PixelFormat::Abgr => u32::from_be_bytes([0xff, b, g, r]),
+            PixelFormat::Rgba => u32::from_be_bytes([r, g, b, 0xff]),
+        }
+    }
+
+    /// unpacks a pixel in this format to a `0xRRGGBB` color, the alpha is discarded
+    pub fn unpack(self, pixel: u32) -> u32 {
+        let [r, g, b] = match (self, pixel.to_be_bytes()) {
+            (PixelFormat::ZeroRgb | PixelFormat::Argb, [_, r, g, b]) => [r, g, b],
+            (PixelFormat::Abgr, [_, b, g, r]) => [r, g, b],
+            (PixelFormat::Rgba, [r, g, b, _]) => [r, g, b],
+        };
+
+        u32::from_be_bytes([0, r, g, b])
+    }
+}
+
+/// how the pixels of images and sprites are combined with the pixels already in the bitmap, the
+/// result is then mixed with the bitmap by the opacity of the drawing
+#[derive(Clone, Default)]
+pub enum BlendMode {
+    /// the pixels are replaced
+    #[default]
+    Normal,
+    /// the colors are summed, for glows and lights
+    Add,
+    /// the colors are multiplied, for shadows and tinted glass
+    Multiply,
+    /// the inverted colors are multiplied, a softer version of `Add`
+    Screen,
+    /// the color of the image is subtracted from the bitmap
+    Subtract,
+    /// the pixels are combined by a user provided function, that gets the `0xAARRGGBB` pixel of
+    /// the image and the `0xRRGGBB` color under it and returns the `0xRRGGBB` color to draw
+    Custom(Arc<dyn Fn(u32, u32) -> u32 + Send + Sync>),
+}
+
+impl BlendMode {
+    /// a blend mode combining the pixels with a function, for dithering, palettized blending or
+    /// other effects the other modes can't do
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::BlendMode;
+    ///
+    /// // keeps the brightest of the two colors, channel by channel
+    /// let lighten = BlendMode::custom(|argb, under| {
+    ///     let ([_, r, g, b], [_, under_r, under_g, under_b]) = (argb.to_be_bytes(), under.to_be_bytes());
+    ///     u32::from_be_bytes([0, r.max(under_r), g.max(under_g), b.max(under_b)])
+    /// });
+    /// ```
+    pub fn custom(blend: impl Fn(u32, u32) -> u32 + Send + Sync + 'static) -> Self {
+        BlendMode::Custom(Arc::new(blend))
+    }
+
+    /// combines a `0xAARRGGBB` pixel with the `0xRRGGBB` color under it, only the custom blend
+    /// modes use the alpha
+    fn blend(&self, argb: u32, under: u32) -> u32 {
+        let channel: fn(u8, u8) -> u8 = match self {
+            BlendMode::Normal => |c, _| c,
+            BlendMode::Add => |c, under| c.saturating_add(under),
+            BlendMode::Multiply => |c, under| (c as u16 * under as u16 / 255) as u8,
+            BlendMode::Screen => {
+                |c, under| 255 - ((255 - c) as u16 * (255 - under) as u16 / 255) as u8
+            }
+            BlendMode::Subtract => |c, under| under.saturating_sub(c),
+            BlendMode::Custom(blend) => return blend(argb, under) & 0xffffff,
+        };
+
+        let ([_, r, g, b], [_, under_r, under_g, under_b]) =
+            (argb.to_be_bytes(), under.to_be_bytes());
+        u32::from_be_bytes([
+            0,
+            channel(r, under_r),
+            channel(g, under_g),
+            channel(b, under_b),
+        ])
+    }
+}
+
+impl fmt::Debug for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlendMode::Normal => write!(f, "Normal"),
+            BlendMode::Add => write!(f, "Add"),
+            BlendMode::Multiply => write!(f, "Multiply"),
+            BlendMode::Screen => write!(f, "Screen"),
+            BlendMode::Subtract => write!(f, "Subtract"),
+            BlendMode::Custom(_) => f.debug_tuple("Custom").finish_non_exhaustive(),
+        }
+    }
 }
 
+/// the alpha below which image pixels are not drawn, halfway between transparent and opaque
+const DEFAULT_ALPHA_THRESHOLD: u8 = 128;
+
 /// abstraction over the bitmap, to subdivide in into virtual pixels (for a pixelated look)
-struct PixelGrid {
-    bitmap: Bitmap,
+///
+/// it's created by a tile grid, use `TileGrid::pixel_grid_mut` to draw single virtual pixels
+/// under the tiles
+pub struct PixelGrid<S = Vec<u32>> {
+    bitmap: Bitmap<S>,
     width: usize,
     height: usize,
-    /// side length of a virtual pixel in screen pixels (is a float because of approximations)
-    pixel_size: f64,
+    /// width and height of a virtual pixel in screen pixels (are floats because of
+    /// approximations)
+    pixel_width: f64,
+    pixel_height: f64,
+    /// width of a virtual pixel divided by its height, 1.0 for square pixels
+    pixel_aspect: f64,
+    scale_mode: ScaleMode,
+    /// the point of the bitmap the grid is placed on when it doesn't fill it
+    alignment: Anchor,
     /// the pixel grid is either clamped by the height or the width of the window
     clamped_by: ClampType,
-    /// offset in the pixel grid in respect to the bitmap caused by the clamping,
-    /// can be an offset in the x or y coordinate, depending on the clamp type
-    pixel_offset: usize,
+    /// offset of the pixel grid in respect to the bitmap caused by the clamping
+    pixel_offset: (usize, usize),
+    /// image pixels with an alpha below this are not drawn
+    alpha_threshold: u8,
+    blend_mode: BlendMode,
+    /// first screen column of every column of virtual pixels, and the one after the last
+    column_starts: Vec<usize>,
+    /// first screen row of every row of virtual pixels, and the one after the last
+    row_starts: Vec<usize>,
 }
 
-enum ClampType {
+/// how the virtual pixels are scaled to fill the bitmap
+///
+/// # Examples
+///
+/// ```
+/// use tiley::{Bitmap, ScaleMode, SpriteSheet, TileGrid};
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 800 * 240], 800, 240);
+/// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+///
+/// // 8:5 pixels, 80 of them in the 240 screen rows, so they are 4.8x3 screen pixels
+/// tile_grid.pixel_grid_mut().set_pixel_aspect(1.6)?;
+/// assert_eq!(tile_grid.pixel_dimensions().1, 3.0);
+///
+/// // 3 screen pixels tall, and 3 times the aspect ratio rounded wide
+/// tile_grid.pixel_grid_mut().set_scale_mode(ScaleMode::Integer);
+/// assert_eq!(tile_grid.pixel_dimensions(), (5.0, 3.0));
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScaleMode {
+    /// the grid is as big as the bitmap allows, the virtual pixels can be a fraction of a screen
+    /// pixel bigger and so some of them are a screen pixel wider or taller than the others
+    #[default]
+    Fit,
+    /// the virtual pixels are a whole number of screen pixels, all the same size, and the
+    /// letterbox bars take the rest of the bitmap. it's pixel perfect, but the grid can be
+    /// smaller than with `Fit`. the pixels are NxN screen pixels when they are square, with an
+    /// aspect ratio the side limited by the bitmap is N screen pixels and the other one is N
+    /// times the aspect ratio, rounded, so they are only close to it, like 5x3 for 8:5 pixels
+    Integer,
+    /// the grid covers the whole bitmap without letterbox bars, the virtual pixels are scaled
+    /// separately in width and in height, so they are stretched when the bitmap doesn't have
+    /// the aspect ratio of the grid. the aspect ratio of the pixels is ignored
+    Stretch,
+}
+
+/// which side of the bitmap limits the size of the pixel grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClampType {
+    /// the grid fills the bitmap height, the bars are on the left and right
     Height,
+    /// the grid fills the bitmap width, the bars are on the top and bottom
     Width,
 }
 
 /// abstraction over the pixel grid, to subdivide the pixel grid into tiles, and draw images on the
 /// tiles
-pub struct TileGrid {
-    pixel_grid: PixelGrid,
+pub struct TileGrid<S = Vec<u32>> {
+    pixel_grid: PixelGrid<S>,
     width: usize,
     height: usize,
-    // side lenght of a tile, in virtual pixels
-    tile_size: usize,
+    // width and height of a tile, in virtual pixels
+    tile_width: usize,
+    tile_height: usize,
     sprite_sheet: SpriteSheet,
+    /// sheets added after the main one, the `SheetId` n is at the index n - 1
+    extra_sheets: Vec<SpriteSheet>,
+    letterbox_color: Option<u32>,
+    /// sprites queued during the frame, drawn by `present`
+    queued_sprites: Vec<QueuedSprite>,
+    /// `0xRRGGBB` colors of the sprites of an `IndexedSpriteSheet`
+    palette: Box<[u32; 256]>,
+    /// sprite of the cursor, drawn by `present` over everything else
+    cursor_sprite: Option<usize>,
+    /// where the cursor is drawn at the end of this frame, in virtual pixels
+    cursor_position: Option<(isize, isize)>,
+    /// effects applied to every frame by `present`, with the ambient tint and the crt filter
+    post_pipeline: PostPipeline<S>,
 }
 
-struct SpriteSheet {
-    image: RgbImage,
-    // side lenght of a sprite, in pixels
-    sprite_size: usize,
-    id_to_coords: fn(sprite_id: usize) -> (usize, usize),
+/// a sprite waiting to be drawn by `TileGrid::present`
+struct QueuedSprite {
+    z: i32,
+    /// top left corner, in virtual pixels
+    position: (isize, isize),
+    sprite_id: usize,
 }
 
-impl Bitmap {
-    /// constructs a bitmap abstraction on top of a Vec<u32>
-    ///
-    /// # Examples
-    /// ```
-    /// use tiley::Bitmap;
-    ///
-    /// let buffer = vec![0; 600 * 200];
-    /// let bitmap = Bitmap::from_vec(buffer, 600, 200);
-    /// ```
-    pub fn from_vec(buffer: Vec<u32>, width: usize, height: usize) -> Self {
-        debug_assert!(width * height == buffer.len());
+/// handle to one of the sprite sheets of a tile grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SheetId(usize);
 
-        Self {
-            buffer,
-            width,
-            height,
-        }
-    }
+impl SheetId {
+    /// the sheet the tile grid was created with
+    pub const MAIN: SheetId = SheetId(0);
+}
 
-    pub fn as_vec(&self) -> &Vec<u32> {
-        &self.buffer
+/// something that identifies a sprite, to draw tiles with an enum instead of plain ids. it's
+/// implemented for `usize`, the id of the sprite in the sheet
+///
+/// # Examples
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use tiley::{Bitmap, SpriteId, SpriteSheet, TileGrid};
+///
+/// #[derive(Clone, Copy)]
+/// enum Tile {
+///     Grass,
+///     Water,
+///     Wall,
+/// }
+///
+/// impl SpriteId for Tile {
+///     fn id(&self) -> usize {
+///         *self as usize
+///     }
+/// }
+///
+/// // green grass, blue water and a gray wall
+/// let sheet = RgbaImage::from_fn(24, 8, |x, _| match x / 8 {
+///     0 => Rgba([0, 255, 0, 255]),
+///     1 => Rgba([0, 0, 255, 255]),
+///     _ => Rgba([128, 128, 128, 255]),
+/// });
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+///
+/// tile_grid.draw_tile((3, 4), Tile::Water);
+/// tile_grid.draw_tile((4, 4), 2);
+/// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x0000ff);
+/// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8, 4 * 8)), 0x808080);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+pub trait SpriteId {
+    /// the id of the sprite in the sprite sheet
+    fn id(&self) -> usize;
+}
+
+impl SpriteId for usize {
+    fn id(&self) -> usize {
+        *self
     }
+}
 
-    pub fn dimensions(&self) -> (usize, usize) {
-        (self.width, self.height)
+/// mirroring of a sprite when it's drawn, so characters can face both ways without duplicated
+/// art. the flips can be combined with `|`
+///
+/// # Examples
+///
+/// ```
+/// use tiley::Flip;
+///
+/// let flip = Flip::X | Flip::Y;
+/// assert!(flip.x() && flip.y());
+/// assert_eq!(Flip::default(), Flip::NONE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Flip {
+    x: bool,
+    y: bool,
+}
+
+impl Flip {
+    pub const NONE: Flip = Flip { x: false, y: false };
+    /// mirrors the sprite horizontally, the left column is drawn on the right
+    pub const X: Flip = Flip { x: true, y: false };
+    /// mirrors the sprite vertically, the top row is drawn at the bottom
+    pub const Y: Flip = Flip { x: false, y: true };
+
+    /// whether the sprite is mirrored horizontally
+    pub fn x(self) -> bool {
+        self.x
     }
 
-    /// completely fills in the bitmap with a single color.
-    /// useful to color the background or delete the previous frame
-    ///
-    /// # Examples
-    /// ```
-    /// use tiley::Bitmap;
-    ///
-    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
-    /// bitmap.fill(0xffffff);
-    /// assert!(bitmap.as_vec().iter().all(|p| *p == 0xffffff));
-    /// ```
-    pub fn fill(&mut self, color: u32) {
-        self.buffer.iter_mut().for_each(|p| *p = color);
+    /// whether the sprite is mirrored vertically
+    pub fn y(self) -> bool {
+        self.y
     }
+}
 
-    fn draw_pixel(&mut self, (x, y): (usize, usize), color: u32) {
-        debug_assert!(x < self.width);
-        debug_assert!(y < self.height);
+impl BitOr for Flip {
+    type Output = Flip;
 
-        self.buffer[x + y * self.width] = color;
+    fn bitor(self, other: Flip) -> Flip {
+        Flip {
+            x: self.x || other.x,
+            y: self.y || other.y,
+        }
     }
+}
 
-    /// this will draw a rectangle on the window by specifying the top left pixel (x1, y1) and bottom
-    /// right pixel (x2, y2)
-    fn draw_rectangle_pixels(
-        &mut self,
-        (x1, y1): (usize, usize),
-        (x2, y2): (usize, usize),
-        color: u32,
-    ) {
-        debug_assert!(x1 < x2);
-        debug_assert!(y1 < y2);
-        debug_assert!(x2 < self.width);
-        debug_assert!(y2 < self.height);
+/// the sprites of a panel drawn with `TileGrid::draw_nine_slice`, the corners are drawn once,
+/// the edges are repeated along the borders and the center fills the inside
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NineSlice {
+    /// top left, top right, bottom left and bottom right corners
+    pub corners: [usize; 4],
+    /// top, bottom, left and right edges
+    pub edges: [usize; 4],
+    pub center: usize,
+}
 
-        for x in x1..=x2 {
-            for y in y1..=y2 {
-                self.draw_pixel((x, y), color);
-            }
+/// clockwise rotation of a sprite when it's drawn, so pipes, arrows and rails need the art for
+/// only one direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rotation {
+    #[default]
+    None,
+    Cw90,
+    Cw180,
+    Cw270,
+}
+
+/// a filter applied to the colors of a sprite when it's drawn, for paused or disabled states
+/// without duplicating the art
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DrawEffect {
+    #[default]
+    None,
+    /// the colors are replaced by their luminance
+    Grayscale,
+    /// every channel is inverted, black becomes white
+    Invert,
+}
+
+/// how the pixels of a sprite are moved and recolored when it's drawn, the sprite is flipped
+/// first and then rotated
+#[derive(Clone, Copy)]
+struct SpriteTransform {
+    flip: Flip,
+    rotation: Rotation,
+    /// `0xRRGGBB` color the pixels are multiplied by, white keeps them as they are
+    tint: u32,
+    /// filter applied to the pixels before the tint
+    effect: DrawEffect,
+    coverage: Coverage,
+}
+
+impl Default for SpriteTransform {
+    fn default() -> Self {
+        SpriteTransform {
+            flip: Flip::NONE,
+            rotation: Rotation::None,
+            tint: 0xffffff,
+            effect: DrawEffect::None,
+            coverage: Coverage::OPAQUE,
         }
     }
 }
 
-impl PixelGrid {
-    fn new(bitmap: Bitmap, width: usize, height: usize) -> Self {
-        let clamped_by =
-            match (bitmap.width as f64 / width as f64) < (bitmap.height as f64 / height as f64) {
-                true => ClampType::Width,
-                false => ClampType::Height,
-            };
+impl SpriteTransform {
+    fn flipped(flip: Flip) -> Self {
+        SpriteTransform {
+            flip,
+            ..Default::default()
+        }
+    }
 
-        let pixel_size = match clamped_by {
-            ClampType::Height => bitmap.height as f64 / height as f64,
-            ClampType::Width => bitmap.width as f64 / width as f64,
-        };
+    fn rotated(rotation: Rotation) -> Self {
+        SpriteTransform {
+            rotation,
+            ..Default::default()
+        }
+    }
 
-        let pixel_offset = match clamped_by {
-            ClampType::Height => bitmap.width - (pixel_size * width as f64) as usize,
-            ClampType::Width => bitmap.height - (pixel_size * height as f64) as usize,
-        } / 2;
+    fn tinted(tint: u32) -> Self {
+        SpriteTransform {
+            tint,
+            ..Default::default()
+        }
+    }
 
-        PixelGrid {
-            bitmap,
-            width,
-            height,
-            clamped_by,
-            pixel_size,
-            pixel_offset,
+    fn with_effect(effect: DrawEffect) -> Self {
+        SpriteTransform {
+            effect,
+            ..Default::default()
         }
     }
 
-    /// this will draw a "virtual" pixel in the pixel grid, which is a square in the bitmap
-    fn draw_virtual_pixel(&mut self, (x, y): (usize, usize), color: u32) {
-        debug_assert!(x < self.width);
-        debug_assert!(y < self.height);
+    /// the opacity goes from 0.0 for invisible to 1.0 for opaque
+    fn with_opacity(opacity: f32) -> Self {
+        let coverage = Coverage {
+            opacity: (opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+            ..Coverage::OPAQUE
+        };
 
-        // calculate the square coordinates in the bitmap
-        let (x1, y1) = (self.pixel_size * x as f64, self.pixel_size * y as f64);
-        let (x1, y1) = (x1 as usize, y1 as usize);
+        SpriteTransform {
+            coverage,
+            ..Default::default()
+        }
+    }
 
-        let (x2, y2) = (
-            self.pixel_size * (x + 1) as f64,
-            self.pixel_size * (y + 1) as f64,
-        );
-        let (x2, y2) = (x2 as usize, y2 as usize);
-        let (x2, y2) = (x2 - 1, y2 - 1);
+    /// the silhouette of the sprite in black, mixed with what is under it by the opacity
+    fn shadow(opacity: f32) -> Self {
+        SpriteTransform {
+            tint: 0x000000,
+            ..Self::with_opacity(opacity)
+        }
+    }
 
-        // offset caused by clamping
-        let (dx, dy) = match self.clamped_by {
-            ClampType::Height => (self.pixel_offset, 0),
-            ClampType::Width => (0, self.pixel_offset),
+    /// the pixels are mixed with what is under them by their alpha
+    fn overlay() -> Self {
+        let coverage = Coverage {
+            by_alpha: true,
+            ..Coverage::OPAQUE
         };
-        let (x1, y1) = (x1 + dx, y1 + dy);
-        let (x2, y2) = (x2 + dx, y2 + dy);
 
-        self.bitmap.draw_rectangle_pixels((x1, y1), (x2, y2), color);
+        SpriteTransform {
+            coverage,
+            ..Default::default()
+        }
     }
 
-    /// function to draw an image mapping the image pixels to the PixelGrid virtual pixels
-    fn draw_image(&mut self, (x, y): (usize, usize), image: SubImage<&RgbImage>) {
-        let (image_width, image_height) = image.dimensions();
+    /// width and height of a `width`x`height` sprite after the transform
+    fn dimensions(self, (width, height): (usize, usize)) -> (usize, usize) {
+        match self.rotation {
+            Rotation::None | Rotation::Cw180 => (width, height),
+            Rotation::Cw90 | Rotation::Cw270 => (height, width),
+        }
+    }
+
+    /// the pixel of a `width`x`height` sprite that is drawn at `(x, y)` of the transformed sprite
+    fn source_pixel(
+        self,
+        (x, y): (usize, usize),
+        (width, height): (usize, usize),
+    ) -> (usize, usize) {
+        let (x, y) = match self.rotation {
+            Rotation::None => (x, y),
+            Rotation::Cw90 => (y, height - 1 - x),
+            Rotation::Cw180 => (width - 1 - x, height - 1 - y),
+            Rotation::Cw270 => (width - 1 - y, x),
+        };
+        let x = if self.flip.x { width - 1 - x } else { x };
+        let y = if self.flip.y { height - 1 - y } else { y };
 
-        debug_assert!(x + image_width as usize - 1 < self.width);
-        debug_assert!(y + image_height as usize - 1 < self.height);
+        (x, y)
+    }
 
-        for dx in 0..image_width {
-            for dy in 0..image_height {
-                let color = image.get_pixel(dx, dy);
-                let color = u32::from_be_bytes([0, color.0[0], color.0[1], color.0[2]]);
-                self.draw_virtual_pixel((x + dx as usize, y + dy as usize), color);
+    /// a `0xAARRGGBB` pixel of the sprite after the effect and the tint, the alpha is kept
+    fn color(self, argb: u32) -> u32 {
+        let argb = match self.effect {
+            DrawEffect::None => argb,
+            DrawEffect::Grayscale => {
+                let [a, r, g, b] = argb.to_be_bytes();
+                // integer approximation of the rec. 601 luma, the weights add up to 256
+                let luma = ((r as u32 * 77 + g as u32 * 150 + b as u32 * 29) >> 8) as u8;
+                u32::from_be_bytes([a, luma, luma, luma])
             }
+            DrawEffect::Invert => argb ^ 0xffffff,
+        };
+
+        if self.tint == 0xffffff {
+            return argb;
         }
+
+        let [a, r, g, b] = argb.to_be_bytes();
+        let [_, tint_r, tint_g, tint_b] = self.tint.to_be_bytes();
+        let multiply = |channel: u8, tint: u8| (channel as u16 * tint as u16 / 255) as u8;
+
+        u32::from_be_bytes([
+            a,
+            multiply(r, tint_r),
+            multiply(g, tint_g),
+            multiply(b, tint_b),
+        ])
     }
 }
 
-impl SpriteSheet {
-    fn new(path: &Path, sprite_size: usize) -> Self {
-        dbg!(path);
-        let image = ImageReader::open(path)
-            .expect("error opening the image")
-            .decode()
-            .expect("error decoding the image");
+/// how much the pixels drawn from an image cover the pixels under them
+#[derive(Clone, Copy)]
+struct Coverage {
+    /// from 0 for invisible to 255 for opaque
+    opacity: u8,
+    /// whether the pixels are also mixed by their own alpha, instead of being skipped when the
+    /// alpha is below the threshold
+    by_alpha: bool,
+}
+
+impl Coverage {
+    const OPAQUE: Coverage = Coverage {
+        opacity: u8::MAX,
+        by_alpha: false,
+    };
+}
+
+/// anything that can give the pixels of sprites by id, to draw tiles with
+/// `TileGrid::draw_tile_with`. it's implemented by `SpriteSheet` and by a `Vec` of images, and it
+/// can be implemented for sprites generated at runtime
+///
+/// # Examples
+///
+/// ```
+/// use image::Rgba;
+/// use tiley::{Bitmap, SpriteSheet, SpriteSource, TileGrid};
+///
+/// /// a checkerboard for every team, the sprite id is the team
+/// struct TeamSprites(Vec<[u8; 3]>);
+///
+/// impl SpriteSource for TeamSprites {
+///     fn sprite_dimensions(&self, sprite_id: usize) -> Option<(usize, usize)> {
+///         (sprite_id < self.0.len()).then_some((8, 8))
+///     }
+///
+///     fn sprite_pixel(&self, sprite_id: usize, (x, y): (usize, usize)) -> Rgba<u8> {
+///         let [r, g, b] = self.0[sprite_id];
+///         match (x + y) % 2 {
+///             0 => Rgba([r, g, b, 255]),
+///             _ => Rgba([0, 0, 0, 0]),
+///         }
+///     }
+/// }
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+/// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+///
+/// let teams = TeamSprites(vec![[255, 0, 0], [0, 0, 255]]);
+/// tile_grid.draw_tile_with((3, 4), &teams, 1);
+///
+/// // the blue team, with the transparent squares not drawn
+/// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x0000ff);
+/// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 + 1, 4 * 8)), 0x000000);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+pub trait SpriteSource {
+    /// width and height of the sprite in pixels, or `None` if there is no sprite with the id
+    fn sprite_dimensions(&self, sprite_id: usize) -> Option<(usize, usize)>;
+
+    /// color of a pixel of the sprite, the coordinates are always inside the sprite dimensions
+    fn sprite_pixel(&self, sprite_id: usize, position: (usize, usize)) -> Rgba<u8>;
+}
+
+/// builder for a tile grid, to avoid passing every option to `TileGrid::new`
+pub struct TileGridBuilder {
+    width: usize,
+    height: usize,
+    /// width and height of a tile, in virtual pixels
+    tile_dimensions: Option<(usize, usize)>,
+    sprite_sheet: Option<SpriteSheetSource>,
+    letterbox_color: Option<u32>,
+    pixel_format: Option<PixelFormat>,
+    sheet_layout: Option<SheetLayout>,
+    pixel_aspect: Option<f64>,
+    scale_mode: Option<ScaleMode>,
+    alignment: Option<Anchor>,
+}
+
+/// where the builder takes the sprite sheet from
+enum SpriteSheetSource {
+    Path(PathBuf),
+    Loaded(Box<SpriteSheet>),
+}
+
+/// an image subdivided in sprites, usually square, that are drawn on the tiles of a tile grid.
+/// it can be loaded once and moved between tile grids, cloning it is cheap because the decoded
+/// image is shared between the clones
+///
+/// the alpha channel of the image is kept, the transparent pixels of a sprite are not drawn (see
+/// `PixelGrid::set_alpha_threshold`) so overlay sprites can be drawn over the terrain
+///
+/// # Examples
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use tiley::{Bitmap, SpriteSheet, TileGrid};
+///
+/// let main_bitmap = Bitmap::from_vec(vec![0; 320 * 160], 320, 160);
+/// let minimap_bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+///
+/// // the image is decoded only once for both grids
+/// let grass = RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255]));
+/// let sprite_sheet = SpriteSheet::from_image(grass, 8)?;
+/// let mut main_view = TileGrid::with_sprite_sheet(main_bitmap, 20, 10, sprite_sheet.clone())?;
+/// let mut minimap = TileGrid::with_sprite_sheet(minimap_bitmap, 20, 10, sprite_sheet)?;
+///
+/// main_view.draw_tile((3, 4), 0);
+/// minimap.draw_tile((3, 4), 0);
+/// assert_eq!(main_view.bitmap().get_pixel((3 * 16, 4 * 16)), 0x00ff00);
+/// assert_eq!(minimap.bitmap().get_pixel((3 * 8, 4 * 8)), 0x00ff00);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Clone)]
+pub struct SpriteSheet {
+    image: Arc<RgbaImage>,
+    /// the pixels of the image packed as `0xAARRGGBB`, in the same order
+    pixels: Arc<[u32]>,
+    // width and height of a sprite, in pixels
+    sprite_width: usize,
+    sprite_height: usize,
+    // border around the sprites and gap between them, in pixels
+    margin: usize,
+    spacing: usize,
+    layout: SheetLayout,
+    /// the region of every sprite for sheets packed as an atlas, `None` for sheets sliced in a
+    /// uniform grid
+    atlas: Option<Vec<Rect>>,
+    /// sprites that can be referred to by name, with their (column, row) coordinates in the sheet
+    names: HashMap<String, (usize, usize)>,
+    /// named sequences of sprites, with their (column, row) coordinates in the sheet
+    animations: HashMap<String, Vec<(usize, usize)>>,
+    /// key/value properties of the sprites, by their (column, row) coordinates in the sheet
+    meta: HashMap<(usize, usize), SpriteMeta>,
+    /// `0xRRGGBB` colors drawn as transparent, kept to key them again when reloading
+    color_keys: Vec<u32>,
+    /// the file the image was loaded from, with its modification time at the last load
+    source: Option<(SheetSource, Option<SystemTime>)>,
+}
+
+/// the file a sprite sheet image is loaded from
+#[derive(Clone)]
+struct SheetSource {
+    path: PathBuf,
+    format: SourceFormat,
+}
+
+#[derive(Clone, Copy)]
+enum SourceFormat {
+    Image,
+    #[cfg(feature = "aseprite")]
+    Aseprite,
+}
+
+/// how the sprite ids are mapped to the sprites of a sprite sheet
+#[derive(Clone, Default)]
+pub enum SheetLayout {
+    /// the ids go from left to right and then from top to bottom, one row after the other
+    #[default]
+    RowMajor,
+    /// only the first row of the sheet is used, the ids go from left to right
+    SingleRow,
+    /// the ids from 0 to `sprite_count - 1` are mapped to (column, row) coordinates in the sheet
+    /// by a user provided function, for irregular or reordered sheets
+    Custom {
+        sprite_count: usize,
+        id_to_coords: Arc<dyn Fn(usize) -> (usize, usize) + Send + Sync>,
+    },
+}
+
+impl SheetLayout {
+    /// a layout mapping the ids from 0 to `sprite_count - 1` with a function
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::SheetLayout;
+    ///
+    /// // the sprites of a 4x4 sheet, column by column
+    /// let layout = SheetLayout::custom(16, |sprite_id| (sprite_id / 4, sprite_id % 4));
+    /// ```
+    pub fn custom(
+        sprite_count: usize,
+        id_to_coords: impl Fn(usize) -> (usize, usize) + Send + Sync + 'static,
+    ) -> Self {
+        SheetLayout::Custom {
+            sprite_count,
+            id_to_coords: Arc::new(id_to_coords),
+        }
+    }
+}
 
-        SpriteSheet {
-            image: image.into(),
-            id_to_coords: linear_translation,
-            sprite_size,
+impl fmt::Debug for SheetLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SheetLayout::RowMajor => write!(f, "RowMajor"),
+            SheetLayout::SingleRow => write!(f, "SingleRow"),
+            SheetLayout::Custom { sprite_count, .. } => f
+                .debug_struct("Custom")
+                .field("sprite_count", sprite_count)
+                .finish_non_exhaustive(),
         }
     }
+}
 
-    fn sprite(&self, sprite_id: usize) -> SubImage<&RgbImage> {
-        let (sprite_x, sprite_y) = (self.id_to_coords)(sprite_id);
+/// a value of the metadata of a sprite, like `solid = true` or `damage = 3`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "manifest", derive(serde::Deserialize), serde(untagged))]
+pub enum MetaValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
 
-        let (image_width, image_height) = self.image.dimensions();
+/// the metadata of a sprite, the properties the game logic needs to know about the tile
+pub type SpriteMeta = HashMap<String, MetaValue>;
 
-        debug_assert!(image_width as usize > (sprite_x + 1) * self.sprite_size - 1);
-        debug_assert!(image_height as usize > (sprite_y + 1) * self.sprite_size - 1);
+impl MetaValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            MetaValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
 
-        // cut out the subimage containing the correct sprite
-        self.image.view(
-            (sprite_x * self.sprite_size) as u32,
-            (sprite_y * self.sprite_size) as u32,
-            self.sprite_size as u32,
-            self.sprite_size as u32,
-        )
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            MetaValue::Int(value) => Some(*value),
+            _ => None,
+        }
     }
-}
 
-fn linear_translation(sprite_id: usize) -> (usize, usize) {
-    (sprite_id, 0)
+    /// the value as a float, integers are converted
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            MetaValue::Float(value) => Some(*value),
+            MetaValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            MetaValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
 }
 
-impl TileGrid {
-    /// creates a new tile grid on top of a bitmap
+impl Bitmap {
+    /// constructs a bitmap abstraction on top of a Vec<u32>
     ///
     /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
     ///
+    /// let buffer = vec![0; 600 * 200];
+    /// let bitmap = Bitmap::from_vec(buffer, 600, 200);
     /// ```
-    /// use tiley::{Bitmap, TileGrid};
     ///
-    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// the buffer length is only checked in debug builds or with the `bounds-checks` feature, use
+    /// `try_from_vec` to get an error instead of a panic
+    pub fn from_vec(buffer: Vec<u32>, width: usize, height: usize) -> Self {
+        check!(width * height == buffer.len());
+
+        Self::from_vec_unchecked(buffer, width, height)
+    }
+
+    /// same as `from_vec`, but returns an error if the buffer length is not `width * height`
+    ///
+    /// # Examples
     /// ```
-    /// ```ignore
-    /// let tile_grid = TileGrid::new(bitmap, 20, 30, 8, std::path::Path::new("./resources/sprite_sheet.png"));
+    /// use tiley::Bitmap;
+    ///
+    /// assert!(Bitmap::try_from_vec(vec![0; 600 * 200], 600, 200).is_ok());
+    /// assert!(Bitmap::try_from_vec(vec![0; 600 * 200], 600, 201).is_err());
     /// ```
-    pub fn new(
-        bitmap: Bitmap,
-        width: usize,
-        height: usize,
-        tile_size: usize,
-        sprite_sheet_path: &Path,
-    ) -> Self {
-        // calculate the pixel_grid dimensions
-        let pixel_grid_width = width * tile_size;
-        let pixel_grid_height = height * tile_size;
-
-        let pixel_grid = PixelGrid::new(bitmap, pixel_grid_width, pixel_grid_height);
+    pub fn try_from_vec(buffer: Vec<u32>, width: usize, height: usize) -> Result<Self, TileyError> {
+        Self::try_from_storage(buffer, width, height)
+    }
 
-        let sprite_sheet = SpriteSheet::new(sprite_sheet_path, tile_size);
+    /// same as `from_vec`, but the buffer length is never checked in release builds, even with
+    /// the `bounds-checks` feature enabled.
+    /// only use it with buffers that are known to be `width * height` long, otherwise drawing
+    /// will end up in the wrong place or panic
+    pub fn from_vec_unchecked(buffer: Vec<u32>, width: usize, height: usize) -> Self {
+        debug_assert!(width * height == buffer.len());
 
         Self {
-            pixel_grid,
+            buffer,
             width,
             height,
-            tile_size,
-            sprite_sheet,
+            format: PixelFormat::default(),
         }
     }
 
-    pub fn dimensions(&self) -> (usize, usize) {
-        (self.width, self.height)
+    pub fn as_vec(&self) -> &Vec<u32> {
+        &self.buffer
     }
 
-    /// draws a tile in the tile coordinates, using a sprite cut from the sprite sheet on the
-    /// sprite id
-    pub fn draw_tile(&mut self, (tile_x, tile_y): (usize, usize), sprite_id: usize) {
-        let sprite = self.sprite_sheet.sprite(sprite_id);
-
-        debug_assert!(tile_x < self.width);
-        debug_assert!(tile_y < self.height);
-
-        // virtual pixel coordinates
-        let (pixel_x, pixel_y) = (tile_x * self.tile_size, tile_y * self.tile_size);
+    /// gives back the ownership of the buffer, to hand it to the windowing crate without copying
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let buffer = vec![0; 600 * 200];
+    /// let buffer = Bitmap::from_vec(buffer, 600, 200).into_vec();
+    /// assert_eq!(buffer.len(), 600 * 200);
+    /// ```
+    pub fn into_vec(self) -> Vec<u32> {
+        self.buffer
+    }
+}
+
+impl<'a> Bitmap<&'a mut [u32]> {
+    /// constructs a bitmap abstraction on top of a borrowed slice, to draw directly in the frame
+    /// buffer of the windowing crate
+    ///
+    /// the slice length is only checked in debug builds or with the `bounds-checks` feature
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut buffer = vec![0; 600 * 200];
+    /// let mut bitmap = Bitmap::from_slice_mut(&mut buffer, 600, 200);
+    /// bitmap.fill(0xffffff);
+    /// assert!(buffer.iter().all(|p| *p == 0xffffff));
+    /// ```
+    pub fn from_slice_mut(buffer: &'a mut [u32], width: usize, height: usize) -> Self {
+        Self::from_storage(buffer, width, height)
+    }
+}
+
+impl<S: PixelStorage> Bitmap<S> {
+    /// constructs a bitmap abstraction on top of any pixel storage
+    ///
+    /// the storage length is only checked in debug builds or with the `bounds-checks` feature,
+    /// use `try_from_storage` to get an error instead of a panic
+    pub fn from_storage(buffer: S, width: usize, height: usize) -> Self {
+        check!(width * height == buffer.as_pixels().len());
+
+        Self {
+            buffer,
+            width,
+            height,
+            format: PixelFormat::default(),
+        }
+    }
+
+    /// same as `from_storage`, but returns an error if the storage length is not
+    /// `width * height`
+    pub fn try_from_storage(buffer: S, width: usize, height: usize) -> Result<Self, TileyError> {
+        let length = buffer.as_pixels().len();
+        if width.checked_mul(height) != Some(length) {
+            return Err(TileyError::BadDimensions(format!(
+                "a buffer of {length} pixels can't be a {width}x{height} bitmap"
+            )));
+        }
+
+        Ok(Self {
+            buffer,
+            width,
+            height,
+            format: PixelFormat::default(),
+        })
+    }
+
+    /// sets the format the colors are packed in when they are drawn, it defaults to
+    /// `PixelFormat::ZeroRgb`
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::{Bitmap, PixelFormat};
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 4 * 4], 4, 4).with_pixel_format(PixelFormat::Rgba);
+    /// bitmap.fill(0xff0000);
+    /// assert_eq!(bitmap[(0, 0)], 0xff0000ff);
+    /// ```
+    pub fn with_pixel_format(mut self, format: PixelFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// the format the colors are packed in when they are drawn
+    pub fn pixel_format(&self) -> PixelFormat {
+        self.format
+    }
+
+    /// the pixels of the bitmap, row by row
+    pub fn as_slice(&self) -> &[u32] {
+        self.buffer.as_pixels()
+    }
+
+    /// reads the color of a pixel of the bitmap, as `0xRRGGBB` whatever the pixel format is.
+    /// index the bitmap to read the packed pixel instead
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// bitmap.fill(0xff00ff);
+    /// assert_eq!(bitmap.get_pixel((599, 199)), 0xff00ff);
+    /// ```
+    pub fn get_pixel(&self, (x, y): (usize, usize)) -> u32 {
+        self.format.unpack(self[(x, y)])
+    }
+
+    /// the packed pixels of a row of the bitmap, from left to right
+    pub fn row(&self, y: usize) -> &[u32] {
+        check!(y < self.height, "y {y} is not in 0..{}", self.height);
+
+        &self.buffer.as_pixels()[y * self.width..(y + 1) * self.width]
+    }
+
+    /// iterates over the coordinates and colors of all the pixels of the bitmap, row by row. the
+    /// colors are `0xRRGGBB` like in `get_pixel`
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0, 1, 2, 3, 4, 5], 3, 2);
+    /// let pixels: Vec<_> = bitmap.pixels().collect();
+    /// assert_eq!(pixels[4], ((1, 1), 4));
+    /// ```
+    pub fn pixels(&self) -> impl Iterator<Item = ((usize, usize), u32)> + '_ {
+        let (width, format) = (self.width, self.format);
+
+        self.buffer
+            .as_pixels()
+            .iter()
+            .enumerate()
+            .map(move |(i, pixel)| ((i % width, i / width), format.unpack(*pixel)))
+    }
+
+    /// gives back the buffer the bitmap was constructed on
+    pub fn into_inner(self) -> S {
+        self.buffer
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// the rectangle covered by the bitmap, in screen pixels
+    pub fn bounds(&self) -> Rect {
+        Rect::new((0, 0), self.width, self.height)
+    }
+
+    /// completely fills in the bitmap with a single color.
+    /// useful to color the background or delete the previous frame
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// bitmap.fill(0xffffff);
+    /// assert!(bitmap.as_vec().iter().all(|p| *p == 0xffffff));
+    /// ```
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        let pixel = self.format.pack(color);
+        self.buffer.as_pixels_mut().fill(pixel);
+    }
+
+    /// this will fill a rectangle of the bitmap with a color, the part of the rectangle outside the
+    /// bitmap is ignored
+    fn fill_rect(&mut self, rect: Rect, color: u32) {
+        let Some(rect) = rect.intersect(&self.bounds()) else {
+            return;
+        };
+        let (x, y) = (rect.x as usize, rect.y as usize);
+
+        let pixel = self.format.pack(color);
+        let width = self.width;
+        let pixels = self.buffer.as_pixels_mut();
+        for row in y..y + rect.height {
+            pixels[x + row * width..x + rect.width + row * width].fill(pixel);
+        }
+    }
+
+    /// combines a `0xAARRGGBB` pixel with the pixels of a rectangle of the bitmap by the blend
+    /// mode, and mixes the result with them by the opacity. 0 leaves the pixels as they are
+    fn blend_rect(&mut self, rect: Rect, argb: u32, opacity: u8, blend_mode: &BlendMode) {
+        let Some(rect) = rect.intersect(&self.bounds()) else {
+            return;
+        };
+        let (x, y) = (rect.x as usize, rect.y as usize);
+
+        let format = self.format;
+        let width = self.width;
+        let pixels = self.buffer.as_pixels_mut();
+        for row in y..y + rect.height {
+            for pixel in &mut pixels[x + row * width..x + rect.width + row * width] {
+                let under = format.unpack(*pixel);
+                *pixel = format.pack(mix(blend_mode.blend(argb, under), under, opacity));
+            }
+        }
+    }
+}
+
+impl<S: PixelStorage> Index<(usize, usize)> for Bitmap<S> {
+    type Output = u32;
+
+    /// the packed pixel at the (x, y) coordinates.
+    /// the coordinates are only checked in debug builds or with the `bounds-checks` feature, so
+    /// without them a too big x reads from the next row
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::Bitmap;
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// bitmap[(10, 20)] = 0xff00ff;
+    /// assert_eq!(bitmap[(10, 20)], 0xff00ff);
+    /// ```
+    fn index(&self, (x, y): (usize, usize)) -> &u32 {
+        check!(x < self.width, "x {x} is not in 0..{}", self.width);
+        check!(y < self.height, "y {y} is not in 0..{}", self.height);
+
+        &self.buffer.as_pixels()[x + y * self.width]
+    }
+}
+
+impl<S: PixelStorage> IndexMut<(usize, usize)> for Bitmap<S> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut u32 {
+        check!(x < self.width, "x {x} is not in 0..{}", self.width);
+        check!(y < self.height, "y {y} is not in 0..{}", self.height);
+
+        &mut self.buffer.as_pixels_mut()[x + y * self.width]
+    }
+}
+
+impl<S: PixelStorage> PixelGrid<S> {
+    fn new(bitmap: Bitmap<S>, width: usize, height: usize) -> Self {
+        let mut pixel_grid = PixelGrid {
+            bitmap,
+            width,
+            height,
+            pixel_width: 1.0,
+            pixel_height: 1.0,
+            pixel_aspect: 1.0,
+            scale_mode: ScaleMode::default(),
+            alignment: Anchor::Center,
+            clamped_by: ClampType::Height,
+            pixel_offset: (0, 0),
+            alpha_threshold: DEFAULT_ALPHA_THRESHOLD,
+            blend_mode: BlendMode::default(),
+            column_starts: Vec::new(),
+            row_starts: Vec::new(),
+        };
+        pixel_grid.layout();
+
+        pixel_grid
+    }
+
+    /// places the virtual pixels in the bitmap, as big as the scale mode allows with their
+    /// aspect ratio and aligned, called again when the bitmap, the scaling or the alignment
+    /// change
+    fn layout(&mut self) {
+        let ((pixel_width, pixel_height), clamped_by) = fit_pixels(
+            self.bitmap.dimensions(),
+            (self.width, self.height),
+            self.pixel_aspect,
+        );
+        let (pixel_width, pixel_height) = match self.scale_mode {
+            ScaleMode::Fit => (pixel_width, pixel_height),
+            ScaleMode::Integer => integer_pixels(
+                (pixel_width, pixel_height),
+                clamped_by,
+                (
+                    self.bitmap.width as f64 / self.width as f64,
+                    self.bitmap.height as f64 / self.height as f64,
+                ),
+                self.pixel_aspect,
+            ),
+            ScaleMode::Stretch => (
+                self.bitmap.width as f64 / self.width as f64,
+                self.bitmap.height as f64 / self.height as f64,
+            ),
+        };
+
+        // the space left by the grid is split between the bars on the two sides by the alignment
+        let (align_x, align_y) = self.alignment.fraction();
+        let bar = |free: usize, fraction: f32| (free as f32 * fraction.clamp(0.0, 1.0)) as usize;
+        let pixel_offset = (
+            bar(
+                self.bitmap.width - (pixel_width * self.width as f64) as usize,
+                align_x,
+            ),
+            bar(
+                self.bitmap.height - (pixel_height * self.height as f64) as usize,
+                align_y,
+            ),
+        );
+
+        // the rectangles are computed once, so drawing doesn't need the floating point math
+        let starts = |length: usize, pixel_size: f64, offset: usize| {
+            (0..=length)
+                .map(|vpixel| (pixel_size * vpixel as f64) as usize + offset)
+                .collect()
+        };
+
+        self.column_starts = starts(self.width, pixel_width, pixel_offset.0);
+        self.row_starts = starts(self.height, pixel_height, pixel_offset.1);
+        (self.pixel_width, self.pixel_height) = (pixel_width, pixel_height);
+        self.clamped_by = clamped_by;
+        self.pixel_offset = pixel_offset;
+    }
+
+    /// offset of the top left corner of the pixel grid in the bitmap, in screen pixels
+    fn offset(&self) -> (usize, usize) {
+        self.pixel_offset
+    }
+
+    /// makes the virtual pixels rectangular, with the width of a pixel divided by its height,
+    /// for art made for the non-square pixels of old machines, like the 8:5 ones of some
+    /// computers. the grid is placed again in the bitmap, so the next frame has to be drawn again
+    /// from scratch
+    ///
+    /// # Errors
+    ///
+    /// fails if the aspect ratio is not a positive number, or if the bitmap is too small to fit
+    /// a virtual pixel for every pixel of the grid with it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 640 * 400], 640, 400);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    ///
+    /// // a 320x200 grid, like the low resolution screen modes of the 80s
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 40, 25, sprite_sheet)?;
+    ///
+    /// tile_grid.pixel_grid_mut().set_pixel_aspect(8.0 / 5.0)?;
+    /// assert_eq!(tile_grid.pixel_dimensions(), (2.0, 1.25));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_pixel_aspect(&mut self, aspect: f64) -> Result<(), TileyError> {
+        if !(aspect.is_finite() && aspect > 0.0) {
+            return Err(TileyError::BadDimensions(format!(
+                "{aspect} is not the aspect ratio of a pixel"
+            )));
+        }
+        check_fits(&self.bitmap, self.width, self.height, aspect)?;
+
+        self.pixel_aspect = aspect;
+        self.layout();
+        Ok(())
+    }
+
+    /// width of a virtual pixel divided by its height, 1.0 for square pixels
+    pub fn pixel_aspect(&self) -> f64 {
+        self.pixel_aspect
+    }
+
+    /// changes how the virtual pixels are scaled to fill the bitmap, `ScaleMode::Fit` by
+    /// default. like `set_pixel_aspect`, the grid is placed again in the bitmap, so the next
+    /// frame has to be drawn again from scratch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, ScaleMode, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // 80 virtual pixels in 200 screen pixels
+    /// assert_eq!(tile_grid.pixel_size(), 2.5);
+    ///
+    /// tile_grid.pixel_grid_mut().set_scale_mode(ScaleMode::Integer);
+    /// assert_eq!(tile_grid.pixel_size(), 2.0);
+    /// assert_eq!(tile_grid.pixel_offset(), (140, 20));
+    ///
+    /// // no bars, the virtual pixels are 3.75x2.5 screen pixels
+    /// tile_grid.pixel_grid_mut().set_scale_mode(ScaleMode::Stretch);
+    /// assert_eq!(tile_grid.pixel_dimensions(), (3.75, 2.5));
+    /// assert_eq!(tile_grid.pixel_offset(), (0, 0));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_scale_mode(&mut self, scale_mode: ScaleMode) {
+        self.scale_mode = scale_mode;
+        self.layout();
+    }
+
+    /// how the virtual pixels are scaled to fill the bitmap
+    pub fn scale_mode(&self) -> ScaleMode {
+        self.scale_mode
+    }
+
+    /// places the grid on a side or a corner of the bitmap when it doesn't fill it, instead of
+    /// in the center, for example to leave the letterbox space on one side for the interface of
+    /// the game. like `set_pixel_aspect`, the next frame has to be drawn again from scratch
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Anchor, Bitmap, ScaleMode, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // the 400x200 grid is in the center of the 600x200 bitmap
+    /// assert_eq!(tile_grid.pixel_offset(), (100, 0));
+    ///
+    /// // all the free space is on the right
+    /// tile_grid.pixel_grid_mut().set_alignment(Anchor::TopLeft);
+    /// assert_eq!(tile_grid.pixel_offset(), (0, 0));
+    ///
+    /// // with integer pixels the 320x160 grid leaves space on every side of the center
+    /// tile_grid.pixel_grid_mut().set_alignment(Anchor::Center);
+    /// tile_grid.pixel_grid_mut().set_scale_mode(ScaleMode::Integer);
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.set_letterbox_color(0x101010);
+    /// assert_eq!(tile_grid.pixel_offset(), (140, 20));
+    /// for (x, y) in [(139, 100), (460, 100), (300, 19), (300, 180)] {
+    ///     assert_eq!(tile_grid.bitmap().get_pixel((x, y)), 0x101010);
+    /// }
+    /// assert_eq!(tile_grid.bitmap().get_pixel((140, 20)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_alignment(&mut self, alignment: Anchor) {
+        self.alignment = alignment;
+        self.layout();
+    }
+
+    /// the point of the bitmap the grid is placed on, `Anchor::Center` by default
+    pub fn alignment(&self) -> Anchor {
+        self.alignment
+    }
+
+    /// fills the parts of the bitmap that are not covered by the pixel grid because of the
+    /// clamping
+    fn fill_letterbox(&mut self, color: u32) {
+        let (bitmap_width, bitmap_height) = self.bitmap.dimensions();
+        let grid = self.screen_rect();
+
+        // the bars on the four sides, the ones the grid touches are empty. the two bars of an axis
+        // can both be non empty when the grid is centered on it, and with `ScaleMode::Integer`
+        // the grid can leave space on both axes
+        let bars = [
+            Rect::new((0, 0), grid.x as usize, bitmap_height),
+            Rect::new(
+                (grid.right(), 0),
+                bitmap_width.saturating_sub(grid.right() as usize),
+                bitmap_height,
+            ),
+            Rect::new((0, 0), bitmap_width, grid.y as usize),
+            Rect::new(
+                (0, grid.bottom()),
+                bitmap_width,
+                bitmap_height.saturating_sub(grid.bottom() as usize),
+            ),
+        ];
+
+        for bar in bars {
+            self.bitmap.fill_rect(bar, color);
+        }
+    }
+
+    /// the rectangle of the pixel grid, in virtual pixels
+    pub fn bounds(&self) -> Rect {
+        Rect::new((0, 0), self.width, self.height)
+    }
+
+    /// the rectangle covered by the whole pixel grid in the bitmap, in screen pixels
+    fn screen_rect(&self) -> Rect {
+        let (width, height) = (
+            (self.pixel_width * self.width as f64) as usize,
+            (self.pixel_height * self.height as f64) as usize,
+        );
+
+        let (x, y) = self.offset();
+        Rect::new((x as isize, y as isize), width, height)
+    }
+
+    /// the rectangle covered by a "virtual" pixel in the bitmap, in screen pixels
+    fn virtual_pixel_rect(&self, (x, y): (usize, usize)) -> Rect {
+        let (x1, x2) = (self.column_starts[x], self.column_starts[x + 1]);
+        let (y1, y2) = (self.row_starts[y], self.row_starts[y + 1]);
+
+        Rect::new((x1 as isize, y1 as isize), x2 - x1, y2 - y1)
+    }
+
+    /// the virtual pixel covering a screen pixel of the bitmap, or `None` if the screen pixel is
+    /// in the letterbox bars. it's the exact inverse of `virtual_pixel_rect`
+    pub fn vpixel_at_screen(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        let (dx, dy) = self.offset();
+        let vpixel_x = self.vpixel_on_axis(x.checked_sub(dx)?, self.width, self.pixel_width)?;
+        let vpixel_y = self.vpixel_on_axis(y.checked_sub(dy)?, self.height, self.pixel_height)?;
+
+        Some((vpixel_x, vpixel_y))
+    }
+
+    /// the virtual pixel covering a screen pixel along one axis, without the clamping offset
+    fn vpixel_on_axis(&self, screen: usize, length: usize, pixel_size: f64) -> Option<usize> {
+        let start = |vpixel: usize| (pixel_size * vpixel as f64) as usize;
+
+        // the division is only a guess because of the rounding in the virtual pixel rectangles
+        let mut vpixel = (screen as f64 / pixel_size) as usize;
+        while vpixel > 0 && start(vpixel) > screen {
+            vpixel -= 1;
+        }
+        while start(vpixel + 1) <= screen {
+            vpixel += 1;
+        }
+
+        (vpixel < length).then_some(vpixel)
+    }
+
+    /// this will draw a "virtual" pixel in the pixel grid, without checking the coordinates in
+    /// release builds
+    fn draw_virtual_pixel_unchecked(&mut self, (x, y): (usize, usize), color: u32) {
+        debug_assert!(x < self.width);
+        debug_assert!(y < self.height);
+
+        let rect = self.virtual_pixel_rect((x, y));
+        self.bitmap.fill_rect(rect, color);
+    }
+
+    /// function to draw an image mapping the image pixels to the PixelGrid virtual pixels, without
+    /// checking the coordinates in release builds
+    fn draw_image_unchecked(&mut self, (x, y): (usize, usize), image: SubImage<&RgbaImage>) {
+        let (image_width, image_height) = image.dimensions();
+        let size = (image_width as usize, image_height as usize);
+
+        self.draw_pixels_scaled_unchecked(
+            (x, y),
+            size,
+            size,
+            Coverage::OPAQUE,
+            |image_x, image_y| rgba_to_argb(image.get_pixel(image_x as u32, image_y as u32)),
+        );
+    }
+
+    /// draws an image of `image_width`x`image_height` pixels, given as `0xAARRGGBB` by `pixel`,
+    /// scaled with nearest neighbor to cover `width`x`height` virtual pixels and blended over the
+    /// bitmap by the coverage, without checking the coordinates in release builds
+    fn draw_pixels_scaled_unchecked(
+        &mut self,
+        (x, y): (usize, usize),
+        (image_width, image_height): (usize, usize),
+        (width, height): (usize, usize),
+        coverage: Coverage,
+        pixel: impl Fn(usize, usize) -> u32,
+    ) {
+        debug_assert!(x + width - 1 < self.width);
+        debug_assert!(y + height - 1 < self.height);
+
+        for dy in 0..height {
+            let image_y = dy * image_height / height;
+            for dx in 0..width {
+                let image_x = dx * image_width / width;
+                self.draw_argb_pixel((x + dx, y + dy), pixel(image_x, image_y), coverage);
+            }
+        }
+    }
+
+    /// same as `draw_pixels_scaled_unchecked`, but the image can go past the borders of the grid
+    fn draw_pixels_scaled_clipped(
+        &mut self,
+        (x, y): (isize, isize),
+        image_size: (usize, usize),
+        size: (usize, usize),
+        coverage: Coverage,
+        pixel: impl Fn(usize, usize) -> u32,
+    ) {
+        let bounds = self.bounds();
+        self.draw_pixels_scaled_clipped_to(bounds, (x, y), image_size, size, coverage, pixel);
+    }
+
+    /// same as `draw_pixels_scaled_clipped`, but only the virtual pixels inside `clip` are drawn
+    fn draw_pixels_scaled_clipped_to(
+        &mut self,
+        clip: Rect,
+        (x, y): (isize, isize),
+        (image_width, image_height): (usize, usize),
+        (width, height): (usize, usize),
+        coverage: Coverage,
+        pixel: impl Fn(usize, usize) -> u32,
+    ) {
+        // part of the scaled image that falls inside the grid and the clipping rectangle
+        let image_rect = Rect::new((x, y), width, height);
+        let Some(visible) = image_rect
+            .intersect(&self.bounds())
+            .and_then(|visible| visible.intersect(&clip))
+        else {
+            return;
+        };
+
+        for pixel_y in visible.y..visible.bottom() {
+            let image_y = pixel_y.abs_diff(y) * image_height / height;
+            for pixel_x in visible.x..visible.right() {
+                let image_x = pixel_x.abs_diff(x) * image_width / width;
+                let argb = pixel(image_x, image_y);
+                self.draw_argb_pixel((pixel_x as usize, pixel_y as usize), argb, coverage);
+            }
+        }
+    }
+
+    /// draws a sprite of a sheet from its packed pixels, scaled to cover `size` virtual pixels
+    fn draw_sprite_unchecked(
+        &mut self,
+        position: (usize, usize),
+        sprite_sheet: &SpriteSheet,
+        coords: (usize, usize),
+        size: (usize, usize),
+        transform: SpriteTransform,
+    ) {
+        let identity = |argb| argb;
+        self.draw_sprite_mapped_unchecked(
+            position,
+            sprite_sheet,
+            coords,
+            size,
+            transform,
+            identity,
+        );
+    }
+
+    /// same as `draw_sprite_unchecked`, but the `0xAARRGGBB` pixels of the sprite go through
+    /// `map` before the transform recolors them
+    fn draw_sprite_mapped_unchecked(
+        &mut self,
+        (x, y): (usize, usize),
+        sprite_sheet: &SpriteSheet,
+        coords: (usize, usize),
+        size: (usize, usize),
+        transform: SpriteTransform,
+        map: impl Fn(u32) -> u32,
+    ) {
+        let (dimensions, pixel) = sprite_sheet.transformed_sprite(coords, transform, map);
+        self.draw_pixels_scaled_unchecked((x, y), dimensions, size, transform.coverage, pixel);
+    }
+
+    /// same as `draw_sprite_unchecked`, but the sprite can go past the borders of the grid
+    fn draw_sprite_clipped(
+        &mut self,
+        (x, y): (isize, isize),
+        sprite_sheet: &SpriteSheet,
+        coords: (usize, usize),
+        size: (usize, usize),
+        transform: SpriteTransform,
+    ) {
+        let bounds = self.bounds();
+        self.draw_sprite_clipped_to(bounds, (x, y), sprite_sheet, coords, size, transform);
+    }
+
+    /// same as `draw_sprite_clipped`, but only the virtual pixels inside `clip` are drawn
+    fn draw_sprite_clipped_to(
+        &mut self,
+        clip: Rect,
+        (x, y): (isize, isize),
+        sprite_sheet: &SpriteSheet,
+        coords: (usize, usize),
+        size: (usize, usize),
+        transform: SpriteTransform,
+    ) {
+        let identity = |argb| argb;
+        let (dimensions, pixel) = sprite_sheet.transformed_sprite(coords, transform, identity);
+        let coverage = transform.coverage;
+        self.draw_pixels_scaled_clipped_to(clip, (x, y), dimensions, size, coverage, pixel);
+    }
+
+    /// covers a rectangle of virtual pixels with a `width`x`height` block of `0xAARRGGBB` pixels
+    /// repeated from the top left corner of the grid, the rectangle must be inside the grid
+    fn draw_tiled_pixels_unchecked(
+        &mut self,
+        rect: Rect,
+        pixels: &[u32],
+        (width, height): (usize, usize),
+    ) {
+        let (left, top) = (rect.x as usize, rect.y as usize);
+        let (right, bottom) = (left + rect.width, top + rect.height);
+        debug_assert!(right <= self.width && bottom <= self.height);
+
+        let opaque = pixels
+            .iter()
+            .all(|argb| (argb >> 24) as u8 >= self.alpha_threshold);
+        if !opaque || !matches!(self.blend_mode, BlendMode::Normal) {
+            for y in top..bottom {
+                for x in left..right {
+                    let argb = pixels[y % height * width + x % width];
+                    self.draw_argb_pixel((x, y), argb, Coverage::OPAQUE);
+                }
+            }
+            return;
+        }
+
+        // every virtual pixel is simply overwritten, so a screen row is built once for every
+        // row of virtual pixels and copied on the screen rows it covers
+        let format = self.bitmap.format;
+        let (screen_left, screen_right) = (self.column_starts[left], self.column_starts[right]);
+        let mut line = vec![0; screen_right - screen_left];
+        for y in top..bottom {
+            for x in left..right {
+                let (start, end) = (self.column_starts[x], self.column_starts[x + 1]);
+                let argb = pixels[y % height * width + x % width];
+                line[start - screen_left..end - screen_left].fill(format.pack(argb & 0xffffff));
+            }
+
+            let stride = self.bitmap.width;
+            let bitmap = self.bitmap.buffer.as_pixels_mut();
+            for screen_y in self.row_starts[y]..self.row_starts[y + 1] {
+                bitmap[screen_y * stride + screen_left..screen_y * stride + screen_right]
+                    .copy_from_slice(&line);
+            }
+        }
+    }
+
+    /// draws a `0xAARRGGBB` pixel of an image on a virtual pixel, unless it is too transparent.
+    /// the pixel is combined with what is already in the bitmap by the blend mode, and when it
+    /// doesn't fully cover it the result is mixed with it
+    fn draw_argb_pixel(&mut self, (x, y): (usize, usize), argb: u32, coverage: Coverage) {
+        let alpha = (argb >> 24) as u8;
+        let opacity = match coverage.by_alpha {
+            true => (coverage.opacity as u16 * alpha as u16 / 255) as u8,
+            false if alpha < self.alpha_threshold => return,
+            false => coverage.opacity,
+        };
+
+        match (opacity, &self.blend_mode) {
+            (0, _) => {}
+            (u8::MAX, BlendMode::Normal) => {
+                self.draw_virtual_pixel_unchecked((x, y), argb & 0xffffff)
+            }
+            (_, blend_mode) => {
+                let rect = self.virtual_pixel_rect((x, y));
+                self.bitmap.blend_rect(rect, argb, opacity, blend_mode);
+            }
+        }
+    }
+
+    /// width and height of the grid, in virtual pixels
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// image pixels with an alpha below the threshold are skipped by the image drawing functions,
+    /// so sprites with a transparent background can be drawn over the tiles below them. it
+    /// defaults to 128, 0 draws every pixel and 255 only the fully opaque ones
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // also draw the half transparent pixels of the sprites, like the antialiased borders
+    /// tile_grid.pixel_grid_mut().set_alpha_threshold(64);
+    /// assert_eq!(tile_grid.pixel_grid().alpha_threshold(), 64);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_alpha_threshold(&mut self, alpha_threshold: u8) {
+        self.alpha_threshold = alpha_threshold;
+    }
+
+    pub fn alpha_threshold(&self) -> u8 {
+        self.alpha_threshold
+    }
+
+    /// sets how the images and the sprites drawn from now on are combined with what is already
+    /// in the bitmap. the virtual pixels and the fills are not blended
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, BlendMode, SpriteSheet, TileGrid};
+    ///
+    /// let glow = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 128, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(glow, 8)?)?;
+    ///
+    /// tile_grid.pixel_grid_mut().fill(0x400040);
+    /// tile_grid.pixel_grid_mut().set_blend_mode(BlendMode::Add);
+    /// tile_grid.draw_tile((0, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x4000c0);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+        self.blend_mode = blend_mode;
+    }
+
+    pub fn blend_mode(&self) -> &BlendMode {
+        &self.blend_mode
+    }
+
+    /// fills the whole grid with a single color, the letterbox bars around it are left untouched
+    pub fn fill(&mut self, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        let rect = self.screen_rect();
+        self.bitmap.fill_rect(rect, color);
+    }
+
+    /// this will draw a "virtual" pixel in the pixel grid, which is a square in the bitmap
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // draw a red pixel in the middle of the top left tile
+    /// tile_grid.pixel_grid_mut().draw_virtual_pixel((4, 4), 0xff0000);
+    ///
+    /// // the virtual pixels are 2.5 screen pixels wide, this one covers the screen pixels 10 and
+    /// // 11 of the grid, after the letterbox bar on the left
+    /// let (offset_x, _) = tile_grid.pixel_offset();
+    /// assert_eq!(tile_grid.bitmap().get_pixel((offset_x + 10, 10)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((offset_x + 11, 11)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((offset_x + 12, 12)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_virtual_pixel(&mut self, (x, y): (usize, usize), color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        check!(x < self.width, "x {x} is not in 0..{}", self.width);
+        check!(y < self.height, "y {y} is not in 0..{}", self.height);
+
+        self.draw_virtual_pixel_unchecked((x, y), color);
+    }
+
+    /// function to draw an image mapping the image pixels to the PixelGrid virtual pixels, the
+    /// image must be completely inside the grid. the pixels with an alpha below the alpha
+    /// threshold are skipped, leaving what was drawn under them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{GenericImageView, Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(RgbaImage::new(8, 8), 8)?)?;
+    ///
+    /// // a white sprite with a transparent top left corner
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+    /// sprite.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+    ///
+    /// tile_grid.pixel_grid_mut().fill(0x00ff00);
+    /// tile_grid.pixel_grid_mut().draw_image((0, 0), sprite.view(0, 0, 8, 8));
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((1, 0)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_image(&mut self, (x, y): (usize, usize), image: SubImage<&RgbaImage>) {
+        let (image_width, image_height) = image.dimensions();
+
+        check!(
+            x + image_width as usize <= self.width,
+            "the image is past the right border"
+        );
+        check!(
+            y + image_height as usize <= self.height,
+            "the image is past the bottom border"
+        );
+
+        self.draw_image_unchecked((x, y), image);
+    }
+
+    /// same as `draw_image`, but the image can go past the borders of the pixel grid, even with
+    /// negative coordinates, the part of the image outside the grid is simply not drawn
+    pub fn draw_image_clipped(&mut self, (x, y): (isize, isize), image: SubImage<&RgbaImage>) {
+        let (image_width, image_height) = image.dimensions();
+        let size = (image_width as usize, image_height as usize);
+
+        self.draw_pixels_scaled_clipped(
+            (x, y),
+            size,
+            size,
+            Coverage::OPAQUE,
+            |image_x, image_y| rgba_to_argb(image.get_pixel(image_x as u32, image_y as u32)),
+        );
+    }
+}
+
+/// mixes two `0xRRGGBB` colors, with `weight` from 0 for only `other` to 255 for only `color`
+fn mix(color: u32, other: u32, weight: u8) -> u32 {
+    let ([_, r, g, b], [_, other_r, other_g, other_b]) = (color.to_be_bytes(), other.to_be_bytes());
+    let (weight, other_weight) = (weight as u32, 255 - weight as u32);
+    let channel = |c: u8, other_c: u8| {
+        ((c as u32 * weight + other_c as u32 * other_weight + 127) / 255) as u8
+    };
+
+    u32::from_be_bytes([
+        0,
+        channel(r, other_r),
+        channel(g, other_g),
+        channel(b, other_b),
+    ])
+}
+
+/// packs a pixel of an image as `0xAARRGGBB`
+fn rgba_to_argb(Rgba([r, g, b, a]): Rgba<u8>) -> u32 {
+    u32::from_be_bytes([a, r, g, b])
+}
+
+/// packs the pixels of an image as `0xAARRGGBB`, so the sprites are drawn without converting
+/// them every frame
+fn pack_pixels(image: &RgbaImage) -> Arc<[u32]> {
+    image.pixels().map(|pixel| rgba_to_argb(*pixel)).collect()
+}
+
+/// checks that every virtual pixel of a pixel grid is at least one screen pixel wide in the bitmap
+fn check_fits<S: PixelStorage>(
+    bitmap: &Bitmap<S>,
+    pixel_grid_width: usize,
+    pixel_grid_height: usize,
+    pixel_aspect: f64,
+) -> Result<(), TileyError> {
+    let (bitmap_width, bitmap_height) = bitmap.dimensions();
+    let ((pixel_width, pixel_height), _) = fit_pixels(
+        (bitmap_width, bitmap_height),
+        (pixel_grid_width, pixel_grid_height),
+        pixel_aspect,
+    );
+
+    if pixel_width < 1.0 || pixel_height < 1.0 {
+        return Err(TileyError::BadDimensions(format!(
+            "a {bitmap_width}x{bitmap_height} bitmap can't fit a {pixel_grid_width}x{pixel_grid_height} pixel grid"
+        )));
+    }
+
+    Ok(())
+}
+
+/// the width and height in screen pixels of the virtual pixels of the biggest pixel grid that
+/// fits in the bitmap with the aspect ratio of its pixels, and the side of the bitmap that
+/// limits it
+fn fit_pixels(
+    (bitmap_width, bitmap_height): (usize, usize),
+    (width, height): (usize, usize),
+    pixel_aspect: f64,
+) -> ((f64, f64), ClampType) {
+    // the grid is as wide as its width in pixels times the aspect ratio, in pixel heights
+    let aspect_width = width as f64 * pixel_aspect;
+    let clamped_by =
+        match (bitmap_width as f64 / aspect_width) < (bitmap_height as f64 / height as f64) {
+            true => ClampType::Width,
+            false => ClampType::Height,
+        };
+
+    let pixel_height = match clamped_by {
+        ClampType::Height => bitmap_height as f64 / height as f64,
+        ClampType::Width => bitmap_width as f64 / aspect_width,
+    };
+
+    ((pixel_height * pixel_aspect, pixel_height), clamped_by)
+}
+
+/// the whole numbers of screen pixels of the virtual pixels in `ScaleMode::Integer`, the side
+/// limited by the bitmap is snapped down to `n` and the other one is `n` scaled by the aspect
+/// ratio and rounded, with a smaller `n` when the other side doesn't fit in its maximum size
+fn integer_pixels(
+    (pixel_width, pixel_height): (f64, f64),
+    clamped_by: ClampType,
+    (max_width, max_height): (f64, f64),
+    pixel_aspect: f64,
+) -> (f64, f64) {
+    let (limited, other_max, ratio) = match clamped_by {
+        ClampType::Height => (pixel_height, max_width, pixel_aspect),
+        ClampType::Width => (pixel_width, max_height, 1.0 / pixel_aspect),
+    };
+    // the sizes are rounded a little up, so 2.9999 screen pixels from the aspect ratio are 3
+    let (limited, other_max) = ((limited + 1e-9).floor(), (other_max + 1e-9).floor());
+
+    // the pixels fit in the bitmap, so they are at least 1 screen pixel
+    let (limited, other) = (1..=limited as usize)
+        .rev()
+        .map(|n| (n as f64, (n as f64 * ratio).round().max(1.0)))
+        .find(|&(_, other)| other <= other_max)
+        .unwrap_or((1.0, other_max));
+
+    match clamped_by {
+        ClampType::Height => (other, limited),
+        ClampType::Width => (limited, other),
+    }
+}
+
+/// checks that the regions of an atlas are sprites inside the image
+fn check_regions(image: &RgbaImage, regions: &[Rect]) -> Result<(), TileyError> {
+    let (image_width, image_height) = image.dimensions();
+    let image_rect = Rect::new((0, 0), image_width as usize, image_height as usize);
+
+    if regions.is_empty() {
+        return Err(TileyError::BadDimensions(
+            "an atlas needs at least one region".to_string(),
+        ));
+    }
+    if let Some(region) = regions
+        .iter()
+        .find(|region| region.is_empty() || region.intersect(&image_rect) != Some(**region))
+    {
+        return Err(TileyError::BadDimensions(format!(
+            "the region {region:?} is not a sprite inside a {image_width}x{image_height} atlas"
+        )));
+    }
+
+    Ok(())
+}
+
+/// makes the pixels of the `0xRRGGBB` color transparent
+fn apply_color_key(image: &mut RgbaImage, color: u32) {
+    let [_, r, g, b] = color.to_be_bytes();
+
+    for pixel in image.pixels_mut() {
+        if pixel.0[..3] == [r, g, b] {
+            pixel.0[3] = 0;
+        }
+    }
+}
+
+impl SheetSource {
+    fn new(path: impl Into<PathBuf>, format: SourceFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+
+    /// the modification time of the file, if it can be read
+    fn modified(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+}
+
+impl SpriteSource for SpriteSheet {
+    fn sprite_dimensions(&self, sprite_id: usize) -> Option<(usize, usize)> {
+        self.contains(sprite_id).then(|| {
+            let (width, height) = self.sprite(sprite_id).dimensions();
+            (width as usize, height as usize)
+        })
+    }
+
+    fn sprite_pixel(&self, sprite_id: usize, (x, y): (usize, usize)) -> Rgba<u8> {
+        self.sprite(sprite_id).get_pixel(x as u32, y as u32)
+    }
+}
+
+impl SpriteSource for Vec<RgbaImage> {
+    fn sprite_dimensions(&self, sprite_id: usize) -> Option<(usize, usize)> {
+        self.get(sprite_id)
+            .map(|image| (image.width() as usize, image.height() as usize))
+    }
+
+    fn sprite_pixel(&self, sprite_id: usize, (x, y): (usize, usize)) -> Rgba<u8> {
+        *self[sprite_id].get_pixel(x as u32, y as u32)
+    }
+}
+
+impl SpriteSheet {
+    /// loads a sprite sheet from an image file, the sprites are squares with a side of
+    /// `sprite_size` pixels
+    ///
+    /// # Errors
+    ///
+    /// fails if the image can't be opened or decoded, or if its dimensions are not a multiple of
+    /// `sprite_size`
+    pub fn new(path: impl AsRef<Path>, sprite_size: usize) -> Result<Self, TileyError> {
+        let path = path.as_ref();
+        let image = ImageReader::open(path)?.decode()?;
+
+        Ok(Self::from_image_with_spacing(image, sprite_size, 0, 0)?
+            .with_source(SheetSource::new(path, SourceFormat::Image)))
+    }
+
+    /// same as `new`, but reads the image from any reader, for example a file inside an archive.
+    /// the image format is guessed from its content
+    ///
+    /// # Errors
+    ///
+    /// fails if the image can't be read or decoded, or if its dimensions are not a multiple of
+    /// `sprite_size`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    /// use tiley::SpriteSheet;
+    ///
+    /// // a png file read from somewhere
+    /// let mut png = Cursor::new(Vec::new());
+    /// image::RgbImage::new(32, 16).write_to(&mut png, image::ImageFormat::Png)?;
+    /// png.set_position(0);
+    ///
+    /// let sprite_sheet = SpriteSheet::from_reader(png, 8)?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 8);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_reader(reader: impl Read + Seek, sprite_size: usize) -> Result<Self, TileyError> {
+        let image = ImageReader::new(BufReader::new(reader))
+            .with_guessed_format()?
+            .decode()?;
+
+        Self::from_image_with_spacing(image, sprite_size, 0, 0)
+    }
+
+    /// same as `new`, but with an already decoded image, for example one generated or recolored
+    /// at startup
+    ///
+    /// # Errors
+    ///
+    /// fails if the image dimensions are not a multiple of `sprite_size`
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::SpriteSheet;
+    ///
+    /// let image = image::RgbaImage::from_pixel(32, 16, image::Rgba([255, 0, 0, 255]));
+    /// let sprite_sheet = SpriteSheet::from_image(image, 8)?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 8);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_image(
+        image: impl Into<DynamicImage>,
+        sprite_size: usize,
+    ) -> Result<Self, TileyError> {
+        Self::from_image_with_spacing(image, sprite_size, 0, 0)
+    }
+
+    /// same as `from_image`, but for sheets that keep the sprites apart, with a border of
+    /// `margin` pixels around the sprites and a gap of `spacing` pixels between them
+    ///
+    /// # Errors
+    ///
+    /// fails if the image can't be sliced in whole sprites with the margin and spacing
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::SpriteSheet;
+    ///
+    /// // 1 pixel border around the sheet and 2 pixels between the sprites
+    /// let image = image::RgbImage::new(1 + 8 + 2 + 8 + 2 + 8 + 1, 1 + 8 + 1);
+    /// let sprite_sheet = SpriteSheet::from_image_with_spacing(image, 8, 1, 2)?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 3);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_image_with_spacing(
+        image: impl Into<DynamicImage>,
+        sprite_size: usize,
+        margin: usize,
+        spacing: usize,
+    ) -> Result<Self, TileyError> {
+        Self::from_image_with_dimensions(image, (sprite_size, sprite_size), margin, spacing)
+    }
+
+    /// same as `from_image_with_spacing`, but for rectangular sprites, `sprite_width` pixels
+    /// wide and `sprite_height` pixels tall
+    ///
+    /// # Errors
+    ///
+    /// fails if the image can't be sliced in whole sprites with the margin and spacing
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::SpriteSheet;
+    ///
+    /// // half height sprites for a platformer
+    /// let image = image::RgbImage::new(64, 16);
+    /// let sprite_sheet = SpriteSheet::from_image_with_dimensions(image, (16, 8), 0, 0)?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 8);
+    /// assert_eq!(sprite_sheet.sprite_height(), 8);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_image_with_dimensions(
+        image: impl Into<DynamicImage>,
+        (sprite_width, sprite_height): (usize, usize),
+        margin: usize,
+        spacing: usize,
+    ) -> Result<Self, TileyError> {
+        let image: RgbaImage = image.into().into();
+        let sprite_sheet = SpriteSheet {
+            pixels: pack_pixels(&image),
+            image: Arc::new(image),
+            sprite_width,
+            sprite_height,
+            margin,
+            spacing,
+            layout: SheetLayout::default(),
+            atlas: None,
+            names: HashMap::new(),
+            animations: HashMap::new(),
+            meta: HashMap::new(),
+            color_keys: Vec::new(),
+            source: None,
+        };
+        sprite_sheet.check_sliceable()?;
+
+        Ok(sprite_sheet)
+    }
+
+    /// loads a sprite sheet packed as an atlas, where every sprite has its own region of the
+    /// image, like the ones exported by texture packers. the sprite ids are the indices of the
+    /// regions, and the (column, row) coordinates of a sprite are `(id, 0)`.
+    /// the sprite size is the longest side of the regions, the sprites are scaled to fit the
+    /// tiles when they are drawn
+    ///
+    /// # Errors
+    ///
+    /// fails if there are no regions, or if a region is empty or not completely inside the image
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Rect, SpriteSheet};
+    ///
+    /// let image = image::RgbaImage::new(64, 32);
+    /// let regions = vec![Rect::new((0, 0), 16, 16), Rect::new((16, 0), 48, 32)];
+    ///
+    /// let mut sprite_sheet = SpriteSheet::from_atlas(image, regions)?;
+    /// sprite_sheet.name_sprite("house", (1, 0))?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 2);
+    /// assert_eq!(sprite_sheet.sprite_size(), 48);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn from_atlas(
+        image: impl Into<DynamicImage>,
+        regions: Vec<Rect>,
+    ) -> Result<Self, TileyError> {
+        let image: RgbaImage = image.into().into();
+        check_regions(&image, &regions)?;
+
+        let sprite_size = regions
+            .iter()
+            .map(|region| region.width.max(region.height))
+            .max()
+            .unwrap_or_default();
+
+        Ok(SpriteSheet {
+            pixels: pack_pixels(&image),
+            image: Arc::new(image),
+            sprite_width: sprite_size,
+            sprite_height: sprite_size,
+            margin: 0,
+            spacing: 0,
+            layout: SheetLayout::default(),
+            atlas: Some(regions),
+            names: HashMap::new(),
+            animations: HashMap::new(),
+            meta: HashMap::new(),
+            color_keys: Vec::new(),
+            source: None,
+        })
+    }
+
+    /// checks that the sheet can be sliced in whole sprites
+    fn check_sliceable(&self) -> Result<(), TileyError> {
+        let (image_width, image_height) = self.image.dimensions();
+        let (sprite_width, sprite_height) = (self.sprite_width, self.sprite_height);
+        let (margin, spacing) = (self.margin, self.spacing);
+
+        // the sprites and the gaps between them must exactly fill the image inside the margin
+        let sliceable = |length: usize, sprite_length: usize| {
+            sprite_length > 0
+                && length >= 2 * margin + sprite_length
+                && (length - 2 * margin + spacing).is_multiple_of(sprite_length + spacing)
+        };
+
+        if !sliceable(image_width as usize, sprite_width)
+            || !sliceable(image_height as usize, sprite_height)
+        {
+            return Err(TileyError::BadDimensions(format!(
+                "a {image_width}x{image_height} sprite sheet can't be sliced in sprites of {sprite_width}x{sprite_height} with a margin of {margin} and a spacing of {spacing}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// makes the pixels of the given `0xRRGGBB` color transparent, for old sheets that use a key
+    /// color like magenta instead of an alpha channel. it can be called again to key more colors
+    ///
+    /// # Examples
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let image = image::RgbImage::from_pixel(32, 16, image::Rgb([255, 0, 255]));
+    /// let sprite_sheet = SpriteSheet::from_image(image, 8)?.with_color_key(0xff00ff);
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    /// tile_grid.pixel_grid_mut().fill(0x00ff00);
+    ///
+    /// // the magenta sprite leaves the background visible
+    /// tile_grid.draw_tile((0, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn with_color_key(mut self, color: impl Into<Color>) -> Self {
+        let color = u32::from(color.into());
+        // the image is copied only if it's shared with other sheets
+        apply_color_key(Arc::make_mut(&mut self.image), color);
+        self.pixels = pack_pixels(&self.image);
+        self.color_keys.push(color);
+
+        self
+    }
+
+    /// remembers the file the image was loaded from, to reload it later
+    fn with_source(mut self, source: SheetSource) -> Self {
+        let modified = source.modified();
+        self.source = Some((source, modified));
+        self
+    }
+
+    /// decodes the image file of the sheet again, for example to see the changes to the art
+    /// while the game is running. the names, animations, metadata, layout and color keys are
+    /// kept, for manifests and atlases only the image is read again, and for aseprite files the
+    /// tags are loaded again too. the image is swapped only when the new one is valid, other
+    /// clones of the sheet keep the previous image
+    ///
+    /// # Errors
+    ///
+    /// fails with `TileyError::NoSourceFile` if the sheet was not loaded from a file, if the file
+    /// can't be read or decoded, or if the new image can't be sliced in the sprites of the sheet,
+    /// in which case the sheet is left untouched
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// // after saving the sheet in the image editor
+    /// tile_grid.sprite_sheet_mut().reload()?;
+    /// ```
+    pub fn reload(&mut self) -> Result<(), TileyError> {
+        let Some((source, _)) = &self.source else {
+            return Err(TileyError::NoSourceFile);
+        };
+
+        let mut reloaded = match source.format {
+            SourceFormat::Image => {
+                let image = ImageReader::open(&source.path)?.decode()?.into_rgba8();
+                SpriteSheet {
+                    image: Arc::new(image),
+                    ..self.clone()
+                }
+            }
+            #[cfg(feature = "aseprite")]
+            SourceFormat::Aseprite => {
+                let mut reloaded = SpriteSheet::from_aseprite(&source.path)?;
+                for (name, frames) in &self.animations {
+                    reloaded
+                        .animations
+                        .entry(name.clone())
+                        .or_insert_with(|| frames.clone());
+                }
+                SpriteSheet {
+                    names: self.names.clone(),
+                    meta: self.meta.clone(),
+                    layout: self.layout.clone(),
+                    color_keys: self.color_keys.clone(),
+                    ..reloaded
+                }
+            }
+        };
+
+        for &color in &self.color_keys {
+            apply_color_key(Arc::make_mut(&mut reloaded.image), color);
+        }
+        reloaded.pixels = pack_pixels(&reloaded.image);
+        reloaded.check_reloaded()?;
+
+        reloaded.source = Some((source.clone(), source.modified()));
+        *self = reloaded;
+        Ok(())
+    }
+
+    /// same as `reload`, but only if the file was modified since it was last loaded, so it can
+    /// be called every few frames. returns whether the sheet was reloaded
+    ///
+    /// # Errors
+    ///
+    /// same as `reload`
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// if frame_counter % 60 == 0 {
+    ///     tile_grid.sprite_sheet_mut().reload_if_changed()?;
+    /// }
+    /// ```
+    pub fn reload_if_changed(&mut self) -> Result<bool, TileyError> {
+        let Some((source, modified)) = &self.source else {
+            return Err(TileyError::NoSourceFile);
+        };
+
+        if source.modified() == *modified {
+            return Ok(false);
+        }
+
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// checks that a reloaded image still has every sprite of the sheet
+    fn check_reloaded(&self) -> Result<(), TileyError> {
+        match &self.atlas {
+            Some(regions) => check_regions(&self.image, regions)?,
+            None => self.check_sliceable()?,
+        }
+
+        let (columns, rows) = self.grid_dimensions();
+        let outside = self
+            .names
+            .values()
+            .chain(self.animations.values().flatten())
+            .chain(self.meta.keys())
+            .find(|coords| !self.contains_coords(**coords));
+
+        if let Some((x, y)) = outside {
+            return Err(TileyError::BadDimensions(format!(
+                "the sprite ({x}, {y}) is outside the reloaded sheet of {columns}x{rows} sprites"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// sets how the sprite ids are mapped to the sprites of the sheet, it defaults to
+    /// `SheetLayout::RowMajor`
+    pub fn with_layout(mut self, layout: SheetLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// number of columns and rows of sprites in the sheet
+    fn grid_dimensions(&self) -> (usize, usize) {
+        if let Some(regions) = &self.atlas {
+            return (regions.len(), 1);
+        }
+
+        let (image_width, image_height) = self.image.dimensions();
+
+        let count = |length: u32, sprite_length: usize| {
+            (length as usize - 2 * self.margin + self.spacing) / (sprite_length + self.spacing)
+        };
+
+        (
+            count(image_width, self.sprite_width),
+            count(image_height, self.sprite_height),
+        )
+    }
+
+    /// column and row of the sprite with the given id in the sheet
+    fn id_to_coords(&self, sprite_id: usize) -> (usize, usize) {
+        let (columns, _) = self.grid_dimensions();
+
+        match &self.layout {
+            SheetLayout::RowMajor => (sprite_id % columns, sprite_id / columns),
+            SheetLayout::SingleRow => (sprite_id, 0),
+            SheetLayout::Custom { id_to_coords, .. } => id_to_coords(sprite_id),
+        }
+    }
+
+    /// side length of a sprite, in pixels, for atlases it's the longest side of the regions and
+    /// for rectangular sprites it's the width
+    pub fn sprite_size(&self) -> usize {
+        self.sprite_width
+    }
+
+    /// width of a sprite, in pixels, for atlases it's the longest side of the regions
+    pub fn sprite_width(&self) -> usize {
+        self.sprite_width
+    }
+
+    /// height of a sprite, in pixels, for atlases it's the longest side of the regions
+    pub fn sprite_height(&self) -> usize {
+        self.sprite_height
+    }
+
+    /// number of sprites that can be addressed by id, the ids go from 0 to sprite_count - 1
+    pub fn sprite_count(&self) -> usize {
+        let (columns, rows) = self.grid_dimensions();
+
+        match self.layout {
+            SheetLayout::RowMajor => columns * rows,
+            SheetLayout::SingleRow => columns,
+            SheetLayout::Custom { sprite_count, .. } => sprite_count,
+        }
+    }
+
+    /// checks if the sprite id maps to a sprite that is completely inside the image
+    fn contains(&self, sprite_id: usize) -> bool {
+        if let SheetLayout::Custom { sprite_count, .. } = self.layout {
+            if sprite_id >= sprite_count {
+                return false;
+            }
+        }
+
+        self.contains_coords(self.id_to_coords(sprite_id))
+    }
+
+    /// checks if the sprite at the (column, row) coordinates is completely inside the image
+    fn contains_coords(&self, (sprite_x, sprite_y): (usize, usize)) -> bool {
+        let (columns, rows) = self.grid_dimensions();
+
+        sprite_x < columns && sprite_y < rows
+    }
+
+    fn sprite(&self, sprite_id: usize) -> SubImage<&RgbaImage> {
+        self.sprite_at_coords(self.id_to_coords(sprite_id))
+    }
+
+    fn sprite_at_coords(&self, coords: (usize, usize)) -> SubImage<&RgbaImage> {
+        let rect = self.sprite_rect(coords);
+
+        self.image.view(
+            rect.x as u32,
+            rect.y as u32,
+            rect.width as u32,
+            rect.height as u32,
+        )
+    }
+
+    /// the region of the image with the sprite at the (column, row) coordinates
+    fn sprite_rect(&self, (sprite_x, sprite_y): (usize, usize)) -> Rect {
+        debug_assert!(self.contains_coords((sprite_x, sprite_y)));
+
+        if let Some(regions) = &self.atlas {
+            return regions[sprite_x];
+        }
+
+        let (stride_x, stride_y) = (
+            self.sprite_width + self.spacing,
+            self.sprite_height + self.spacing,
+        );
+        Rect::new(
+            (
+                (self.margin + sprite_x * stride_x) as isize,
+                (self.margin + sprite_y * stride_y) as isize,
+            ),
+            self.sprite_width,
+            self.sprite_height,
+        )
+    }
+
+    /// the dimensions of the sprite at the (column, row) coordinates once transformed, and its
+    /// `0xAARRGGBB` pixels by their position in the transformed sprite. the pixels go through
+    /// `map` before the transform recolors them
+    fn transformed_sprite<'a>(
+        &'a self,
+        coords: (usize, usize),
+        transform: SpriteTransform,
+        map: impl Fn(u32) -> u32 + 'a,
+    ) -> ((usize, usize), impl Fn(usize, usize) -> u32 + 'a) {
+        let (stride, rect) = (self.image.width() as usize, self.sprite_rect(coords));
+        let (left, top) = (rect.x as usize, rect.y as usize);
+        let (width, height) = (rect.width, rect.height);
+        let pixels = &self.pixels;
+
+        let pixel = move |dx, dy| {
+            let (dx, dy) = transform.source_pixel((dx, dy), (width, height));
+            transform.color(map(pixels[(top + dy) * stride + left + dx]))
+        };
+        (transform.dimensions((width, height)), pixel)
+    }
+
+    /// the `0xAARRGGBB` pixels of the sprite at the (column, row) coordinates scaled to
+    /// `width`x`height` pixels with nearest neighbor, row by row
+    fn scaled_sprite_pixels(
+        &self,
+        coords: (usize, usize),
+        (width, height): (usize, usize),
+    ) -> Vec<u32> {
+        let (stride, rect) = (self.image.width() as usize, self.sprite_rect(coords));
+        let (left, top) = (rect.x as usize, rect.y as usize);
+
+        (0..width * height)
+            .map(|i| {
+                let (x, y) = (
+                    i % width * rect.width / width,
+                    i / width * rect.height / height,
+                );
+                self.pixels[(top + y) * stride + left + x]
+            })
+            .collect()
+    }
+
+    /// gives a name to the sprite at the (column, row) coordinates in the sheet, so it can be
+    /// drawn with `TileGrid::draw_tile_named`. giving a name that is already used moves it to
+    /// the new sprite
+    ///
+    /// # Errors
+    ///
+    /// fails if the coordinates are outside the sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::SpriteSheet;
+    ///
+    /// let mut sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// sprite_sheet.name_sprite("grass", (0, 0))?;
+    /// sprite_sheet.name_sprite("player_idle_0", (3, 1))?;
+    /// assert_eq!(sprite_sheet.sprite_coords("grass"), Some((0, 0)));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn name_sprite(
+        &mut self,
+        name: impl Into<String>,
+        coords: (usize, usize),
+    ) -> Result<(), TileyError> {
+        if !self.contains_coords(coords) {
+            let (columns, rows) = self.grid_dimensions();
+            return Err(TileyError::BadDimensions(format!(
+                "the sprite ({}, {}) is outside a sheet of {columns}x{rows} sprites",
+                coords.0, coords.1
+            )));
+        }
+
+        self.names.insert(name.into(), coords);
+        Ok(())
+    }
+
+    /// the (column, row) coordinates of the sprite with the given name
+    pub fn sprite_coords(&self, name: &str) -> Option<(usize, usize)> {
+        self.names.get(name).copied()
+    }
+
+    /// gives a name to a sequence of sprites, given by their (column, row) coordinates in the
+    /// sheet, so they can be drawn with `TileGrid::draw_animation_frame`
+    ///
+    /// # Errors
+    ///
+    /// fails if the animation has no frames or if a frame is outside the sheet
+    pub fn add_animation(
+        &mut self,
+        name: impl Into<String>,
+        frames: Vec<(usize, usize)>,
+    ) -> Result<(), TileyError> {
+        let name = name.into();
+        if frames.is_empty() {
+            return Err(TileyError::BadDimensions(format!(
+                "the animation {name:?} has no frames"
+            )));
+        }
+        if let Some((x, y)) = frames.iter().copied().find(|f| !self.contains_coords(*f)) {
+            let (columns, rows) = self.grid_dimensions();
+            return Err(TileyError::BadDimensions(format!(
+                "the frame ({x}, {y}) of the animation {name:?} is outside a sheet of {columns}x{rows} sprites"
+            )));
+        }
+
+        self.animations.insert(name, frames);
+        Ok(())
+    }
+
+    /// the (column, row) coordinates of the frames of the animation with the given name
+    pub fn animation(&self, name: &str) -> Option<&[(usize, usize)]> {
+        self.animations.get(name).map(Vec::as_slice)
+    }
+
+    /// sets a property of the sprite at the (column, row) coordinates in the sheet, like
+    /// whether the tile is solid, so the game logic can read it with `TileGrid::sprite_meta`.
+    /// setting a property again replaces its value
+    ///
+    /// # Errors
+    ///
+    /// fails if the coordinates are outside the sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{MetaValue, SpriteSheet};
+    ///
+    /// let mut sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// sprite_sheet.set_sprite_meta((1, 0), "solid", MetaValue::Bool(true))?;
+    /// sprite_sheet.set_sprite_meta((1, 0), "damage", MetaValue::Int(3))?;
+    ///
+    /// let meta = sprite_sheet.sprite_meta((1, 0)).unwrap();
+    /// assert_eq!(meta.get("damage"), Some(&MetaValue::Int(3)));
+    /// assert!(sprite_sheet.sprite_meta((0, 0)).is_none());
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_sprite_meta(
+        &mut self,
+        coords: (usize, usize),
+        key: impl Into<String>,
+        value: MetaValue,
+    ) -> Result<(), TileyError> {
+        if !self.contains_coords(coords) {
+            let (columns, rows) = self.grid_dimensions();
+            return Err(TileyError::BadDimensions(format!(
+                "the sprite ({}, {}) is outside a sheet of {columns}x{rows} sprites",
+                coords.0, coords.1
+            )));
+        }
+
+        self.meta
+            .entry(coords)
+            .or_default()
+            .insert(key.into(), value);
+        Ok(())
+    }
+
+    /// the properties of the sprite at the (column, row) coordinates, or `None` if it has none
+    pub fn sprite_meta(&self, coords: (usize, usize)) -> Option<&SpriteMeta> {
+        self.meta.get(&coords)
+    }
+}
+
+impl<S: PixelStorage> TileGrid<S> {
+    /// creates a new tile grid on top of a bitmap
+    ///
+    /// # Errors
+    ///
+    /// fails if the sprite sheet can't be opened or decoded, if its dimensions are not a multiple
+    /// of the tile size, or if the bitmap is too small to fit a virtual pixel for every pixel of
+    /// the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// ```
+    /// ```ignore
+    /// let tile_grid = TileGrid::new(bitmap, 20, 30, 8, std::path::Path::new("./resources/sprite_sheet.png"))?;
+    /// ```
+    pub fn new(
+        bitmap: Bitmap<S>,
+        width: usize,
+        height: usize,
+        tile_size: usize,
+        sprite_sheet_path: &Path,
+    ) -> Result<Self, TileyError> {
+        let sprite_sheet = SpriteSheet::new(sprite_sheet_path, tile_size)?;
+
+        Self::with_sprite_sheet(bitmap, width, height, sprite_sheet)
+    }
+
+    /// same as `new`, but with an already loaded sprite sheet, the tiles are as wide and as tall
+    /// as the sprites of the sheet
+    ///
+    /// # Errors
+    ///
+    /// fails if the bitmap is too small to fit a virtual pixel for every pixel of the grid
+    pub fn with_sprite_sheet(
+        bitmap: Bitmap<S>,
+        width: usize,
+        height: usize,
+        sprite_sheet: SpriteSheet,
+    ) -> Result<Self, TileyError> {
+        let tile_dimensions = (sprite_sheet.sprite_width(), sprite_sheet.sprite_height());
+
+        Self::with_tile_dimensions(bitmap, width, height, tile_dimensions, sprite_sheet)
+    }
+
+    /// same as `with_sprite_sheet`, but with a tile size that can be different from the sprite
+    /// size of the sheet, the sprites are scaled with nearest neighbor to fit the tiles
+    ///
+    /// # Errors
+    ///
+    /// fails if the bitmap is too small to fit a virtual pixel for every pixel of the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a blue sprite with a white top left corner
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]));
+    /// sprite.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// // 8x8 sprites drawn on 16x16 tiles
+    /// let bitmap = Bitmap::from_vec(vec![0; 320 * 160], 320, 160);
+    /// let sprite_sheet = SpriteSheet::from_image(sprite, 8)?;
+    /// let mut tile_grid = TileGrid::with_tile_size(bitmap, 20, 10, 16, sprite_sheet)?;
+    /// tile_grid.draw_tile((3, 4), 0);
+    ///
+    /// // every pixel of the sprite covers 2x2 virtual pixels
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 16 + 1, 4 * 16 + 1)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 16 + 2, 4 * 16)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 16 - 1, 5 * 16 - 1)), 0x0000ff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn with_tile_size(
+        bitmap: Bitmap<S>,
+        width: usize,
+        height: usize,
+        tile_size: usize,
+        sprite_sheet: SpriteSheet,
+    ) -> Result<Self, TileyError> {
+        Self::with_tile_dimensions(bitmap, width, height, (tile_size, tile_size), sprite_sheet)
+    }
+
+    /// same as `with_tile_size`, but with rectangular tiles, `tile_width` virtual pixels wide and
+    /// `tile_height` virtual pixels tall
+    ///
+    /// # Errors
+    ///
+    /// fails if the bitmap is too small to fit a virtual pixel for every pixel of the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // half height tiles for a platformer
+    /// let bitmap = Bitmap::from_vec(vec![0; 640 * 320], 640, 320);
+    /// let sprite_sheet = SpriteSheet::from_image_with_dimensions(
+    ///     image::RgbImage::new(64, 16),
+    ///     (16, 8),
+    ///     0,
+    ///     0,
+    /// )?;
+    /// let mut tile_grid = TileGrid::with_tile_dimensions(bitmap, 20, 40, (16, 8), sprite_sheet)?;
+    /// tile_grid.draw_tile((3, 4), 0);
+    /// assert_eq!(tile_grid.tile_dimensions(), (16, 8));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn with_tile_dimensions(
+        bitmap: Bitmap<S>,
+        width: usize,
+        height: usize,
+        (tile_width, tile_height): (usize, usize),
+        sprite_sheet: SpriteSheet,
+    ) -> Result<Self, TileyError> {
+        if width == 0 || height == 0 || tile_width == 0 || tile_height == 0 {
+            return Err(TileyError::BadDimensions(format!(
+                "a tile grid of {width}x{height} tiles of {tile_width}x{tile_height} is empty"
+            )));
+        }
+
+        // calculate the pixel_grid dimensions
+        let pixel_grid_width = width * tile_width;
+        let pixel_grid_height = height * tile_height;
+
+        check_fits(&bitmap, pixel_grid_width, pixel_grid_height, 1.0)?;
+
+        let pixel_grid = PixelGrid::new(bitmap, pixel_grid_width, pixel_grid_height);
+
+        Ok(Self {
+            pixel_grid,
+            width,
+            height,
+            tile_width,
+            tile_height,
+            sprite_sheet,
+            extra_sheets: Vec::new(),
+            letterbox_color: None,
+            queued_sprites: Vec::new(),
+            palette: Box::new([0; 256]),
+            cursor_sprite: None,
+            cursor_position: None,
+            post_pipeline: PostPipeline::new(),
+        })
+    }
+
+    /// replaces the sprite sheet the tiles are drawn from, for example to change the theme of the
+    /// game, and returns the previous one
+    ///
+    /// if the sprite size of the new sheet is not the tile size of the grid, the sprites are
+    /// scaled to fit the tiles
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let summer = RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255]));
+    /// let winter = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(summer, 8)?)?;
+    ///
+    /// let winter = SpriteSheet::from_image(winter, tile_grid.tile_size())?;
+    /// let summer = tile_grid.set_sprite_sheet(winter);
+    /// tile_grid.draw_tile((3, 4), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0xffffff);
+    ///
+    /// // and back to the summer
+    /// tile_grid.set_sprite_sheet(summer);
+    /// tile_grid.draw_tile((3, 4), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_sprite_sheet(&mut self, sprite_sheet: SpriteSheet) -> SpriteSheet {
+        std::mem::replace(&mut self.sprite_sheet, sprite_sheet)
+    }
+
+    /// the sprite sheet the tiles are drawn from
+    pub fn sprite_sheet(&self) -> &SpriteSheet {
+        &self.sprite_sheet
+    }
+
+    /// mutable access to the sprite sheet the tiles are drawn from, for example to name its
+    /// sprites
+    pub fn sprite_sheet_mut(&mut self) -> &mut SpriteSheet {
+        &mut self.sprite_sheet
+    }
+
+    /// adds another sprite sheet to draw tiles from with `draw_tile_from`, so terrain,
+    /// characters and ui can live in different images. the main sheet has the `SheetId::MAIN` id
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SheetId, SpriteSheet, TileGrid};
+    ///
+    /// let terrain = RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255]));
+    /// let characters = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(terrain, 8)?)?;
+    ///
+    /// let characters = SpriteSheet::from_image(characters, tile_grid.tile_size())?;
+    /// let characters = tile_grid.add_sprite_sheet(characters);
+    ///
+    /// // the same sprite id in two sheets
+    /// tile_grid.draw_tile_from((3, 4), characters, 0);
+    /// tile_grid.draw_tile_from((4, 4), SheetId::MAIN, 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8, 4 * 8)), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn add_sprite_sheet(&mut self, sprite_sheet: SpriteSheet) -> SheetId {
+        self.extra_sheets.push(sprite_sheet);
+        SheetId(self.extra_sheets.len())
+    }
+
+    /// the sprite sheet with the given id, or `None` if the id is from another grid
+    pub fn sheet(&self, sheet_id: SheetId) -> Option<&SpriteSheet> {
+        match sheet_id.0 {
+            0 => Some(&self.sprite_sheet),
+            n => self.extra_sheets.get(n - 1),
+        }
+    }
+
+    /// mutable access to the sprite sheet with the given id, or `None` if the id is from another
+    /// grid
+    pub fn sheet_mut(&mut self, sheet_id: SheetId) -> Option<&mut SpriteSheet> {
+        match sheet_id.0 {
+            0 => Some(&mut self.sprite_sheet),
+            n => self.extra_sheets.get_mut(n - 1),
+        }
+    }
+
+    /// moves the grid to a new bitmap, for example after the window was resized, keeping the
+    /// loaded sprite sheet. the layout of the grid is computed again for the new bitmap size and
+    /// the letterbox bars are filled if a letterbox color was set.
+    /// returns the previous bitmap
+    ///
+    /// # Errors
+    ///
+    /// fails if the new bitmap is too small to fit a virtual pixel for every pixel of the grid,
+    /// in which case the grid keeps drawing on the previous bitmap
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let (width, height) = window.get_size();
+    /// let bitmap = Bitmap::from_vec(vec![0; width * height], width, height);
+    /// tile_grid.resize(bitmap)?;
+    /// ```
+    pub fn resize(&mut self, bitmap: Bitmap<S>) -> Result<Bitmap<S>, TileyError> {
+        let (width, height) = self.pixel_grid.dimensions();
+        check_fits(&bitmap, width, height, self.pixel_grid.pixel_aspect)?;
+
+        let previous = std::mem::replace(&mut self.pixel_grid.bitmap, bitmap);
+        self.pixel_grid.layout();
+
+        if let Some(color) = self.letterbox_color {
+            self.pixel_grid.fill_letterbox(color);
+        }
+
+        Ok(previous)
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// the rectangle of the grid, in tiles
+    pub fn bounds(&self) -> Rect {
+        Rect::new((0, 0), self.width, self.height)
+    }
+
+    /// the bitmap the grid draws on
+    pub fn bitmap(&self) -> &Bitmap<S> {
+        &self.pixel_grid.bitmap
+    }
+
+    /// mutable access to the bitmap the grid draws on, for example to fill it before drawing a
+    /// new frame
+    pub fn bitmap_mut(&mut self) -> &mut Bitmap<S> {
+        &mut self.pixel_grid.bitmap
+    }
+
+    /// destroys the grid and gives back the bitmap it was drawing on
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let buffer = tile_grid.into_bitmap().into_vec();
+    /// window.update_with_buffer(&buffer, 600, 200)?;
+    /// ```
+    pub fn into_bitmap(self) -> Bitmap<S> {
+        self.pixel_grid.bitmap
+    }
+
+    /// the pixel grid under the tiles
+    pub fn pixel_grid(&self) -> &PixelGrid<S> {
+        &self.pixel_grid
+    }
+
+    /// mutable access to the pixel grid under the tiles, to draw single virtual pixels
+    pub fn pixel_grid_mut(&mut self) -> &mut PixelGrid<S> {
+        &mut self.pixel_grid
+    }
+
+    /// side length of a tile, in virtual pixels, for rectangular tiles it's the width
+    pub fn tile_size(&self) -> usize {
+        self.tile_width
+    }
+
+    /// width and height of a tile, in virtual pixels
+    pub fn tile_dimensions(&self) -> (usize, usize) {
+        (self.tile_width, self.tile_height)
+    }
+
+    /// side length of a virtual pixel, in screen pixels. it's a float because the bitmap size is
+    /// usually not a multiple of the pixel grid size, so the virtual pixels drawn in the bitmap
+    /// are either `pixel_size.floor()` or `pixel_size.ceil()` screen pixels wide. for
+    /// rectangular pixels it's the width
+    pub fn pixel_size(&self) -> f64 {
+        self.pixel_grid.pixel_width
+    }
+
+    /// width and height of a virtual pixel, in screen pixels, see `PixelGrid::set_pixel_aspect`
+    pub fn pixel_dimensions(&self) -> (f64, f64) {
+        (self.pixel_grid.pixel_width, self.pixel_grid.pixel_height)
+    }
+
+    /// which side of the bitmap limits the size of the grid, with `ScaleMode::Stretch` the grid
+    /// fills both
+    pub fn clamped_by(&self) -> ClampType {
+        self.pixel_grid.clamped_by
+    }
+
+    /// offset of the top left corner of the grid in the bitmap, in screen pixels, caused by the
+    /// letterbox bars and the alignment of the grid. only one of the coordinates can be
+    /// non-zero, depending on `clamped_by`, unless the scale mode is `ScaleMode::Integer`, and
+    /// with `ScaleMode::Stretch` there are no bars
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // convert a point in the bitmap to virtual pixel coordinates
+    /// let (offset_x, offset_y) = tile_grid.pixel_offset();
+    /// let (pixel_width, pixel_height) = tile_grid.pixel_dimensions();
+    /// let pixel_x = ((mouse_x - offset_x) as f64 / pixel_width) as usize;
+    /// let pixel_y = ((mouse_y - offset_y) as f64 / pixel_height) as usize;
+    /// ```
+    pub fn pixel_offset(&self) -> (usize, usize) {
+        self.pixel_grid.offset()
+    }
+
+    /// fills the letterbox bars around the grid with a color, now and at the end of every frame
+    /// by `present`, so they don't keep what was drawn before a resize or a change of the scaling
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.set_letterbox_color(0x101010);
+    ///
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    /// // the bars are on the left and on the right of the grid
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x101010);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((300, 0)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_letterbox_color(&mut self, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        self.letterbox_color = Some(color);
+        self.pixel_grid.fill_letterbox(color);
+    }
+
+    /// stops filling the letterbox bars, they keep what is drawn on them
+    pub fn clear_letterbox_color(&mut self) {
+        self.letterbox_color = None;
+    }
+
+    /// the `0xRRGGBB` color of the letterbox bars, if one was set
+    pub fn letterbox_color(&self) -> Option<u32> {
+        self.letterbox_color
+    }
+
+    /// the virtual pixel at the top left corner of a tile
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid, TilePos, VPixelPos};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // with a tile size of 8
+    /// assert_eq!(tile_grid.tile_to_vpixel(TilePos::new(2, 3)), VPixelPos::new(16, 24));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn tile_to_vpixel(&self, tile: TilePos) -> VPixelPos {
+        VPixelPos::new(tile.x * self.tile_width, tile.y * self.tile_height)
+    }
+
+    /// the tile containing a virtual pixel
+    pub fn vpixel_to_tile(&self, pixel: VPixelPos) -> TilePos {
+        TilePos::new(pixel.x / self.tile_width, pixel.y / self.tile_height)
+    }
+
+    /// the screen pixel at the top left corner of a virtual pixel
+    pub fn vpixel_to_screen(&self, pixel: VPixelPos) -> ScreenPos {
+        let rect = self.pixel_grid.virtual_pixel_rect(pixel.into());
+        ScreenPos::new(rect.x as usize, rect.y as usize)
+    }
+
+    /// the virtual pixel covering a screen pixel, or `None` if the screen pixel is in the
+    /// letterbox bars
+    pub fn screen_to_vpixel(&self, pixel: ScreenPos) -> Option<VPixelPos> {
+        self.pixel_grid
+            .vpixel_at_screen(pixel.into())
+            .map(VPixelPos::from)
+    }
+
+    /// the tile under a screen pixel, for example to know which tile was clicked, or `None` if
+    /// the screen pixel is in the letterbox bars
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// if let Some((mouse_x, mouse_y)) = window.get_mouse_pos(MouseMode::Discard) {
+    ///     if let Some(tile) = tile_grid.tile_at_screen((mouse_x as usize, mouse_y as usize)) {
+    ///         tile_grid.draw_tile(tile, selected_sprite);
+    ///     }
+    /// }
+    /// ```
+    pub fn tile_at_screen(&self, (x, y): (usize, usize)) -> Option<(usize, usize)> {
+        let pixel = self.screen_to_vpixel(ScreenPos::new(x, y))?;
+
+        Some(self.vpixel_to_tile(pixel).into())
+    }
+
+    /// the screen pixel at the top left corner of a tile
+    pub fn tile_to_screen(&self, tile: TilePos) -> ScreenPos {
+        self.vpixel_to_screen(self.tile_to_vpixel(tile))
+    }
+
+    /// the rectangle covered by a tile in the bitmap, in screen pixels, for example to draw a
+    /// selection box with another library
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// let rect = tile_grid.screen_rect_of((2, 3));
+    /// assert_eq!(tile_grid.tile_at_screen((rect.x as usize, rect.y as usize)), Some((2, 3)));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn screen_rect_of(&self, (tile_x, tile_y): (usize, usize)) -> Rect {
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        let last_pixel = (
+            pixel_x + self.tile_width - 1,
+            pixel_y + self.tile_height - 1,
+        );
+
+        let top_left = self.pixel_grid.virtual_pixel_rect((pixel_x, pixel_y));
+        let bottom_right = self.pixel_grid.virtual_pixel_rect(last_pixel);
+
+        Rect::new(
+            (top_left.x, top_left.y),
+            bottom_right.right().abs_diff(top_left.x),
+            bottom_right.bottom().abs_diff(top_left.y),
+        )
+    }
+
+    /// draws a tile in the tile coordinates, using a sprite cut from the sprite sheet on the
+    /// sprite id
+    ///
+    /// the coordinates and the sprite id are only checked in debug builds or with the
+    /// `bounds-checks` feature, use `try_draw_tile` to get an error instead of a panic
+    pub fn draw_tile(&mut self, (tile_x, tile_y): (usize, usize), sprite_id: impl SpriteId) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        self.draw_tile_unchecked((tile_x, tile_y), sprite_id);
+    }
+
+    /// same as `draw_tile`, but the sprite is mirrored horizontally, vertically or both
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, Flip, SpriteSheet, TileGrid};
+    ///
+    /// // a black sprite with a white top left corner
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+    /// sprite.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the white corner ends up in the bottom right
+    /// tile_grid.draw_tile_flipped((0, 0), 0, Flip::X | Flip::Y);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7, 7)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_flipped(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        flip: Flip,
+    ) {
+        self.draw_tile_transformed(
+            (tile_x, tile_y),
+            sprite_id.id(),
+            SpriteTransform::flipped(flip),
+        );
+    }
+
+    /// same as `draw_tile`, but the sprite is rotated clockwise
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, Rotation, SpriteSheet, TileGrid};
+    ///
+    /// // a black sprite with a white top left corner
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 255]));
+    /// sprite.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the white corner ends up in the top right
+    /// tile_grid.draw_tile_rotated((0, 0), 0, Rotation::Cw90);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7, 0)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_rotated(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        rotation: Rotation,
+    ) {
+        self.draw_tile_transformed(
+            (tile_x, tile_y),
+            sprite_id.id(),
+            SpriteTransform::rotated(rotation),
+        );
+    }
+
+    /// same as `draw_tile`, but every pixel of the sprite is multiplied by a `0xRRGGBB` color, for
+    /// damage flashes, team colors or lighting. white draws the sprite as it is
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 128, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // only the red and the half of the green are left
+    /// tile_grid.draw_tile_tinted((0, 0), 0, 0xff8000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xff4000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_tinted(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        tint: impl Into<Color>,
+    ) {
+        let tint = u32::from(tint.into());
+        self.draw_tile_transformed(
+            (tile_x, tile_y),
+            sprite_id.id(),
+            SpriteTransform::tinted(tint),
+        );
+    }
+
+    /// same as `draw_tile`, but the sprite is blended with what is already drawn under it, from
+    /// 0.0 for invisible to 1.0 for opaque, for ghosts, fading ui or placement previews
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // a white ghost over a black background is gray
+    /// tile_grid.draw_tile_alpha((0, 0), 0, 0.5);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x808080);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_alpha(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        opacity: f32,
+    ) {
+        let transform = SpriteTransform::with_opacity(opacity);
+        self.draw_tile_transformed((tile_x, tile_y), sprite_id.id(), transform);
+    }
+
+    /// same as `draw_tile`, but the colors of the sprite go through an effect
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, DrawEffect, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// tile_grid.draw_tile_with_effect((0, 0), 0, DrawEffect::Invert);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ffff);
+    /// tile_grid.draw_tile_with_effect((0, 0), 0, DrawEffect::Grayscale);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x4c4c4c);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_with_effect(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        effect: DrawEffect,
+    ) {
+        let transform = SpriteTransform::with_effect(effect);
+        self.draw_tile_transformed((tile_x, tile_y), sprite_id.id(), transform);
+    }
+
+    /// draws only the shape of a sprite, its visible pixels filled with one `0xRRGGBB` color,
+    /// for hit flashes, shadows or stencils
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a two colored sprite on a transparent background
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 0]));
+    /// sprite.put_pixel(3, 3, Rgba([255, 0, 0, 255]));
+    /// sprite.put_pixel(4, 3, Rgba([0, 0, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the enemy flashes white when hit
+    /// tile_grid.draw_tile_silhouette((0, 0), 0, 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 3)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4, 3)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_silhouette(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        color: impl Into<Color>,
+    ) {
+        let color = u32::from(color.into());
+        let transform = SpriteTransform::default();
+        self.draw_tile_mapped((tile_x, tile_y), sprite_id.id(), transform, |argb| {
+            argb & 0xff000000 | color & 0xffffff
+        });
+    }
+
+    /// same as `draw_tile`, but the sprite is composited over what is already drawn by the alpha
+    /// of its pixels, instead of skipping the pixels below the alpha threshold and covering the
+    /// tile with the others. the transparent pixels leave the tile under them untouched and the
+    /// half transparent ones, like antialiased borders or shadows, are mixed with it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a character with a transparent background and a half transparent shadow
+    /// let mut character = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 0]));
+    /// character.put_pixel(3, 3, Rgba([255, 0, 0, 255]));
+    /// character.put_pixel(3, 7, Rgba([0, 0, 0, 128]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(character, 8)?)?;
+    ///
+    /// // the grass drawn before is kept around the character and darkened by the shadow
+    /// tile_grid.pixel_grid_mut().fill(0x00ff00);
+    /// tile_grid.draw_tile_overlay((0, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 3)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 7)), 0x007f00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_overlay(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+    ) {
+        let transform = SpriteTransform::overlay();
+        self.draw_tile_transformed((tile_x, tile_y), sprite_id.id(), transform);
+    }
+
+    /// same as `draw_tile`, but a shadow is drawn under the sprite first, a black silhouette of
+    /// it moved by an offset in virtual pixels and mixed with the background by an opacity from
+    /// 0.0 to 1.0. the shadow can go past the borders of the tile on the tiles around it, unless
+    /// they are drawn after it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 255, 255, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the shadow falls two virtual pixels to the bottom right
+    /// tile_grid.pixel_grid_mut().fill(0xffffff);
+    /// tile_grid.draw_tile_with_shadow((0, 0), 0, (2, 2), 0.5);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7, 7)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((9, 9)), 0x7f7f7f);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((1, 9)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_with_shadow(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        (offset_x, offset_y): (isize, isize),
+        opacity: f32,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        self.pixel_grid.draw_sprite_clipped(
+            (pixel_x as isize + offset_x, pixel_y as isize + offset_y),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            (self.tile_width, self.tile_height),
+            SpriteTransform::shadow(opacity),
+        );
+
+        self.draw_tile_unchecked((tile_x, tile_y), sprite_id);
+    }
+
+    /// same as `draw_tile`, but with a one virtual pixel outline of a `0xRRGGBB` color around
+    /// the silhouette of the sprite. the outline covers the transparent pixels next to the
+    /// visible ones, the pixels below the alpha threshold or of the color key, and it can go
+    /// past the borders of the tile on the tiles around it, unless they are drawn after it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a red dot on a transparent background
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 0, 0]));
+    /// sprite.put_pixel(3, 3, Rgba([255, 0, 0, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// tile_grid.draw_tile_outlined((0, 0), 0, 0xffff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 3)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((2, 3)), 0xffff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 4)), 0xffff00);
+    /// // the outline doesn't go around the corners
+    /// assert_eq!(tile_grid.bitmap().get_pixel((2, 2)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_outlined(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        outline_color: impl Into<Color>,
+    ) {
+        let outline_color = u32::from(outline_color.into());
+        let sprite_id = sprite_id.id();
+        self.draw_tile((tile_x, tile_y), sprite_id);
+
+        let (width, height) = (self.tile_width, self.tile_height);
+        let coords = self.sprite_sheet.id_to_coords(sprite_id);
+        let pixels = self
+            .sprite_sheet
+            .scaled_sprite_pixels(coords, (width, height));
+        let alpha_threshold = self.pixel_grid.alpha_threshold;
+        let visible = |x: isize, y: isize| {
+            (0..width as isize).contains(&x)
+                && (0..height as isize).contains(&y)
+                && (pixels[y as usize * width + x as usize] >> 24) as u8 >= alpha_threshold
+        };
+
+        let (left, top) = ((tile_x * width) as isize, (tile_y * height) as isize);
+        let bounds = self.pixel_grid.bounds();
+        for y in -1..=height as isize {
+            for x in -1..=width as isize {
+                let edge = !visible(x, y)
+                    && [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                        .into_iter()
+                        .any(|(x, y)| visible(x, y));
+                if edge && bounds.contains((left + x, top + y)) {
+                    let position = ((left + x) as usize, (top + y) as usize);
+                    let argb = 0xff000000 | outline_color;
+                    self.pixel_grid
+                        .draw_argb_pixel(position, argb, Coverage::OPAQUE);
+                }
+            }
+        }
+    }
+
+    /// same as `draw_tile`, but the sprite is combined with what is under it by a function, that
+    /// gets the `0xAARRGGBB` pixel of the sprite and the `0xRRGGBB` color under it and returns
+    /// the `0xRRGGBB` color to draw. the blend mode of the pixel grid is left as it was
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // keeps the green and the blue of the background
+    /// tile_grid.pixel_grid_mut().fill(0x123456);
+    /// tile_grid.draw_tile_blended((0, 0), 0, |argb, under| (argb | under) & 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xff3456);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_blended(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        blend: impl Fn(u32, u32) -> u32 + Send + Sync + 'static,
+    ) {
+        let blend_mode = BlendMode::custom(blend);
+        let previous = std::mem::replace(&mut self.pixel_grid.blend_mode, blend_mode);
+
+        self.draw_tile_transformed((tile_x, tile_y), sprite_id.id(), SpriteTransform::default());
+        self.pixel_grid.blend_mode = previous;
+    }
+
+    /// draws a tile of the main sheet with a transform, checking the arguments like `draw_tile`
+    fn draw_tile_transformed(
+        &mut self,
+        position: (usize, usize),
+        sprite_id: usize,
+        transform: SpriteTransform,
+    ) {
+        self.draw_tile_mapped(position, sprite_id, transform, |argb| argb);
+    }
+
+    /// same as `draw_tile_transformed`, but the `0xAARRGGBB` pixels of the sprite also go
+    /// through `map`
+    fn draw_tile_mapped(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: usize,
+        transform: SpriteTransform,
+        map: impl Fn(u32) -> u32,
+    ) {
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+
+        self.pixel_grid.draw_sprite_mapped_unchecked(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            (self.tile_width, self.tile_height),
+            transform,
+            map,
+        );
+    }
+
+    /// same as `draw_tile`, but using a sprite from one of the sheets added with
+    /// `add_sprite_sheet`
+    ///
+    /// # Panics
+    ///
+    /// panics if the sheet is not in the tile grid, in every build. if the tile or the sprite
+    /// is outside the grid or the sheet, it panics in debug builds or with the `bounds-checks`
+    /// feature
+    pub fn draw_tile_from(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sheet_id: SheetId,
+        sprite_id: impl SpriteId,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+
+        // matching on the fields, to borrow the sheet and the pixel grid at the same time
+        let sprite_sheet = match sheet_id.0 {
+            0 => Some(&self.sprite_sheet),
+            n => self.extra_sheets.get(n - 1),
+        };
+        let Some(sprite_sheet) = sprite_sheet else {
+            panic!("the sheet {sheet_id:?} is not in the tile grid");
+        };
+        check!(
+            sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet {sheet_id:?}"
+        );
+
+        let coords = sprite_sheet.id_to_coords(sprite_id);
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+
+        self.pixel_grid.draw_sprite_unchecked(
+            (pixel_x, pixel_y),
+            sprite_sheet,
+            coords,
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+    }
+
+    /// same as `draw_tile`, but using a sprite from any sprite source, for example sprites
+    /// generated at runtime. the sprite is scaled to fit the tile, and an empty sprite draws
+    /// nothing
+    ///
+    /// # Panics
+    ///
+    /// panics if the source has no sprite with the id, in every build. if the tile is outside
+    /// the grid, it panics in debug builds or with the `bounds-checks` feature
+    pub fn draw_tile_with<T: SpriteSource + ?Sized>(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        source: &T,
+        sprite_id: impl SpriteId,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+
+        let Some((width, height)) = source.sprite_dimensions(sprite_id) else {
+            panic!("sprite {sprite_id} is not in the sprite source");
+        };
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+
+        self.pixel_grid.draw_pixels_scaled_unchecked(
+            (pixel_x, pixel_y),
+            (width, height),
+            (self.tile_width, self.tile_height),
+            Coverage::OPAQUE,
+            |x, y| rgba_to_argb(source.sprite_pixel(sprite_id, (x, y))),
+        );
+    }
+
+    /// draws a sprite that spans `width`x`height` tiles, like a tree or a building, from the
+    /// block of sprites that starts at `sprite_id` and goes to the right and down in the sheet.
+    /// `(tile_x, tile_y)` is the tile of the top left corner
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a sheet 4 sprites wide and 2 tall, every sprite with its own color
+    /// let sheet = RgbaImage::from_fn(32, 16, |x, y| {
+    ///     Rgba([(x / 8 * 64 + 32) as u8, (y / 8 * 255) as u8, 0, 255])
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// // a 2x2 tree made of the sprites 0, 1, 4 and 5
+    /// tile_grid.draw_big_tile((3, 4), 0, (2, 2));
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x200000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8, 4 * 8)), 0x600000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 5 * 8)), 0x20ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8 + 7, 5 * 8 + 7)), 0x60ff00);
+    /// // the tiles around the tree are untouched
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8, 4 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_big_tile(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        (width, height): (usize, usize),
+    ) {
+        let sprite_id = sprite_id.id();
+        if width == 0 || height == 0 {
+            return;
+        }
+
+        check!(
+            tile_x + width <= self.width,
+            "the sprite is past the right border"
+        );
+        check!(
+            tile_y + height <= self.height,
+            "the sprite is past the bottom border"
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (sprite_x, sprite_y) = self.sprite_sheet.id_to_coords(sprite_id);
+        check!(
+            self.sprite_sheet
+                .contains_coords((sprite_x + width - 1, sprite_y + height - 1)),
+            "the {width}x{height} sprites from sprite {sprite_id} are past the sheet border"
+        );
+
+        for dx in 0..width {
+            for dy in 0..height {
+                let (pixel_x, pixel_y) = (
+                    (tile_x + dx) * self.tile_width,
+                    (tile_y + dy) * self.tile_height,
+                );
+
+                self.pixel_grid.draw_sprite_unchecked(
+                    (pixel_x, pixel_y),
+                    &self.sprite_sheet,
+                    (sprite_x + dx, sprite_y + dy),
+                    (self.tile_width, self.tile_height),
+                    SpriteTransform::default(),
+                );
+            }
+        }
+    }
+
+    /// draws a sprite `scale` times bigger than a tile, covering `scale`x`scale` tiles from
+    /// `(tile_x, tile_y)`, for bosses, zoom effects or emphasized icons. the sprite is scaled
+    /// with nearest neighbor, so it stays sharp
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a blue boss with a white top left corner
+    /// let mut boss = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]));
+    /// boss.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(boss, 8)?)?;
+    ///
+    /// // the boss covers the tiles from (3, 4) to (5, 6)
+    /// tile_grid.draw_tile_scaled((3, 4), 0, 3);
+    ///
+    /// // every pixel of the sprite is 3x3 virtual pixels
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 + 2, 4 * 8 + 2)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 + 3, 4 * 8)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((6 * 8 - 1, 7 * 8 - 1)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((6 * 8, 7 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_scaled(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        scale: usize,
+    ) {
+        let sprite_id = sprite_id.id();
+        if scale == 0 {
+            return;
+        }
+
+        check!(
+            tile_x + scale <= self.width,
+            "the sprite is past the right border"
+        );
+        check!(
+            tile_y + scale <= self.height,
+            "the sprite is past the bottom border"
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        let size = (self.tile_width * scale, self.tile_height * scale);
+
+        self.pixel_grid.draw_sprite_unchecked(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            size,
+            SpriteTransform::default(),
+        );
+    }
+
+    /// same as `draw_tile`, but only a rectangle of the sprite is drawn, in the pixels of the
+    /// sprite from its top left corner. the rectangle is drawn where it would be in the whole
+    /// tile and the rest of the tile is left untouched, for reveal effects or for clipping
+    /// against ui panels. the part of the rectangle outside the sprite is ignored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let health = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(health, 8)?)?;
+    ///
+    /// // a health bar filled from the left
+    /// let progress = 0.5;
+    /// let filled = (8.0 * progress) as usize;
+    /// tile_grid.draw_tile_region((3, 4), 0, Rect::new((0, 0), filled, 8));
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 + 3, 4 * 8)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 + 4, 4 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_region(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        region: Rect,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let coords = self.sprite_sheet.id_to_coords(sprite_id);
+        let sprite = self.sprite_sheet.sprite_rect(coords);
+        let Some(region) = region.intersect(&Rect::new((0, 0), sprite.width, sprite.height)) else {
+            return;
+        };
+
+        // the virtual pixels of the tile that show the pixels of the region, the first virtual
+        // pixel showing a sprite pixel is the one after the previous sprite pixels
+        let (tile_width, tile_height) = (self.tile_width, self.tile_height);
+        let scale = |pixel: isize, length: usize, tile_length: usize| {
+            (pixel as usize * tile_length).div_ceil(length)
+        };
+        let (left, right) = (
+            scale(region.x, sprite.width, tile_width),
+            scale(region.right(), sprite.width, tile_width),
+        );
+        let (top, bottom) = (
+            scale(region.y, sprite.height, tile_height),
+            scale(region.bottom(), sprite.height, tile_height),
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * tile_width, tile_y * tile_height);
+        let clip = Rect::new(
+            ((pixel_x + left) as isize, (pixel_y + top) as isize),
+            right - left,
+            bottom - top,
+        );
+
+        self.pixel_grid.draw_sprite_clipped_to(
+            clip,
+            (pixel_x as isize, pixel_y as isize),
+            &self.sprite_sheet,
+            coords,
+            (tile_width, tile_height),
+            SpriteTransform::default(),
+        );
+    }
+
+    /// copies the virtual pixels already drawn on a tile to another tile, without sampling the
+    /// sprite sheet again, for example to duplicate static decorations
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a blue sprite with a white top left corner
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]));
+    /// sprite.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// tile_grid.draw_tile((3, 4), 0);
+    /// tile_grid.pixel_grid_mut().draw_virtual_pixel((3 * 8 + 1, 4 * 8 + 1), 0xff0000);
+    ///
+    /// // the tile (5, 4) gets the sprite and the red pixel
+    /// tile_grid.copy_tile((3, 4), (5, 4));
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8, 4 * 8)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8 + 1, 4 * 8 + 1)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8 + 7, 4 * 8 + 7)), 0x0000ff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn copy_tile(&mut self, (src_x, src_y): (usize, usize), (dst_x, dst_y): (usize, usize)) {
+        check!(
+            src_x < self.width && src_y < self.height,
+            "the tile ({src_x}, {src_y}) is outside the grid"
+        );
+        check!(
+            dst_x < self.width && dst_y < self.height,
+            "the tile ({dst_x}, {dst_y}) is outside the grid"
+        );
+
+        let (tile_width, tile_height) = (self.tile_width, self.tile_height);
+        let (src, dst) = (
+            (src_x * tile_width, src_y * tile_height),
+            (dst_x * tile_width, dst_y * tile_height),
+        );
+        for dy in 0..tile_height {
+            for dx in 0..tile_width {
+                // every screen pixel of a virtual pixel has the same color
+                let rect = self.pixel_grid.virtual_pixel_rect((src.0 + dx, src.1 + dy));
+                let color = self
+                    .pixel_grid
+                    .bitmap
+                    .get_pixel((rect.x as usize, rect.y as usize));
+
+                self.pixel_grid
+                    .draw_virtual_pixel_unchecked((dst.0 + dx, dst.1 + dy), color);
+            }
+        }
+    }
+
+    /// draws the same sprite on every tile of a rectangle of tiles, like a background, in a
+    /// single pass that samples the sprite only once. the part of the rectangle outside the grid
+    /// is ignored
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// // a blue water sprite with a white top left corner
+    /// let mut water = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 255, 255]));
+    /// water.put_pixel(0, 0, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(water, 8)?)?;
+    ///
+    /// // water on the bottom half of the grid
+    /// tile_grid.fill_region(Rect::new((0, 5), 20, 5), 0);
+    ///
+    /// // every tile of the region gets the whole sprite
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 5 * 8)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((19 * 8, 9 * 8)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((19 * 8 + 1, 9 * 8)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 5 * 8 - 1)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn fill_region(&mut self, region: Rect, sprite_id: impl SpriteId) {
+        let sprite_id = sprite_id.id();
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let Some(region) = region.intersect(&self.bounds()) else {
+            return;
+        };
+
+        let (tile_width, tile_height) = (self.tile_width, self.tile_height);
+        let coords = self.sprite_sheet.id_to_coords(sprite_id);
+        let pixels = self
+            .sprite_sheet
+            .scaled_sprite_pixels(coords, (tile_width, tile_height));
+
+        let vpixels = Rect::new(
+            (
+                region.x * tile_width as isize,
+                region.y * tile_height as isize,
+            ),
+            region.width * tile_width,
+            region.height * tile_height,
+        );
+        self.pixel_grid
+            .draw_tiled_pixels_unchecked(vpixels, &pixels, (tile_width, tile_height));
+    }
+
+    /// same as `fill_region`, but on the whole grid
+    pub fn fill_all(&mut self, sprite_id: impl SpriteId) {
+        self.fill_region(self.bounds(), sprite_id);
+    }
+
+    /// draws a row of tiles from `(tile_x, tile_y)` to the right, one for every sprite id, so
+    /// maps stored as flat arrays can be drawn a row at a time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a green grass sprite and a blue water one
+    /// let sheet = RgbaImage::from_fn(16, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([0, 255, 0, 255]),
+    ///     _ => Rgba([0, 0, 255, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// tile_grid.draw_tile_row((2, 4), &[0, 0, 1, 0]);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8, 4 * 8)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8, 4 * 8)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((6 * 8, 4 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_row<T: SpriteId>(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_ids: &[T],
+    ) {
+        check!(
+            tile_x + sprite_ids.len() <= self.width,
+            "the row is past the right border"
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+
+        for (dx, sprite_id) in sprite_ids.iter().enumerate() {
+            let sprite_id = sprite_id.id();
+            check!(
+                self.sprite_sheet.contains(sprite_id),
+                "sprite {sprite_id} is outside the sprite sheet"
+            );
+
+            self.draw_tile_unchecked((tile_x + dx, tile_y), sprite_id);
+        }
+    }
+
+    /// draws a rectangle of tiles `width` tiles wide from `(tile_x, tile_y)`, the sprite ids are
+    /// given row by row, so a whole map stored as a flat array is drawn in a single call. a last
+    /// row shorter than `width` is drawn too
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a green grass sprite and a blue water one
+    /// let sheet = RgbaImage::from_fn(16, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([0, 255, 0, 255]),
+    ///     _ => Rgba([0, 0, 255, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// // a 3 tiles wide map, with a last row of 2 tiles
+    /// let map = [0, 0, 0, 0, 1, 0, 1, 1];
+    /// tile_grid.draw_tile_rect((5, 2), 3, &map);
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((6 * 8, 3 * 8)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7 * 8, 3 * 8)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((6 * 8, 4 * 8)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7 * 8, 4 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_rect<T: SpriteId>(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        width: usize,
+        sprite_ids: &[T],
+    ) {
+        if width == 0 {
+            return;
+        }
+
+        for (dy, row) in sprite_ids.chunks(width).enumerate() {
+            self.draw_tile_row((tile_x, tile_y + dy), row);
+        }
+    }
+
+    /// draws many tiles at once, in any order. the tiles are sorted row by row before they are
+    /// drawn, so the bitmap is written from the top to the bottom, the tiles on the same position
+    /// are still drawn in the order they were given. the tiles from several sheets are drawn with
+    /// `draw_batch_from`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid, TilePos};
+    ///
+    /// // a red, a green and a blue sprite
+    /// let sheet = RgbaImage::from_fn(24, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([255, 0, 0, 255]),
+    ///     1 => Rgba([0, 255, 0, 255]),
+    ///     _ => Rgba([0, 0, 255, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// let changed_tiles = vec![
+    ///     (TilePos::new(3, 9), 0),
+    ///     (TilePos::new(7, 0), 2),
+    ///     (TilePos::new(3, 9), 1),
+    /// ];
+    /// tile_grid.draw_batch(changed_tiles);
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((7 * 8, 0)), 0x0000ff);
+    /// // the green tile was given after the red one on the same position
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 9 * 8)), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_batch<P: Into<TilePos>, T: SpriteId>(
+        &mut self,
+        tiles: impl IntoIterator<Item = (P, T)>,
+    ) {
+        let mut tiles: Vec<(TilePos, usize)> = tiles
+            .into_iter()
+            .map(|(position, sprite_id)| (position.into(), sprite_id.id()))
+            .collect();
+        tiles.sort_by_key(|(position, _)| (position.y, position.x));
+
+        for (TilePos { x, y }, sprite_id) in tiles {
+            self.draw_tile((x, y), sprite_id);
+        }
+    }
+
+    /// same as `draw_batch`, but every tile has the sheet of its sprite, `SheetId::MAIN` or one
+    /// added with `add_sprite_sheet`. the tiles are grouped by sheet, so the sheets are read one
+    /// at a time, and sorted row by row in every group. the tiles on the same position are still
+    /// drawn in the order they were given, even if they are from different sheets
+    ///
+    /// # Panics
+    ///
+    /// panics if a sheet is not in the tile grid, in every build, like `draw_tile_from`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SheetId, SpriteSheet, TileGrid, TilePos};
+    ///
+    /// let terrain = RgbaImage::from_pixel(8, 8, Rgba([0, 255, 0, 255]));
+    /// let characters = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(terrain, 8)?)?;
+    /// let characters = tile_grid.add_sprite_sheet(SpriteSheet::from_image(characters, 8)?);
+    ///
+    /// tile_grid.draw_batch_from(vec![
+    ///     (TilePos::new(5, 5), SheetId::MAIN, 0),
+    ///     (TilePos::new(5, 5), characters, 0),
+    ///     (TilePos::new(0, 0), SheetId::MAIN, 0),
+    /// ]);
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// // the character was given after the terrain below it
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5 * 8, 5 * 8)), 0xff0000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_batch_from<P: Into<TilePos>, T: SpriteId>(
+        &mut self,
+        tiles: impl IntoIterator<Item = (P, SheetId, T)>,
+    ) {
+        // the tiles on a position already given go to a layer drawn later, so grouping them by
+        // sheet doesn't change the order of the tiles on the same position
+        let mut layers: HashMap<TilePos, usize> = HashMap::new();
+        let mut tiles: Vec<(usize, SheetId, TilePos, usize)> = tiles
+            .into_iter()
+            .map(|(position, sheet_id, sprite_id)| {
+                let position = position.into();
+                let layer = layers.entry(position).or_default();
+                *layer += 1;
+                (*layer, sheet_id, position, sprite_id.id())
+            })
+            .collect();
+        tiles.sort_by_key(|&(layer, sheet_id, position, _)| {
+            (layer, sheet_id.0, position.y, position.x)
+        });
+
+        for (_, sheet_id, TilePos { x, y }, sprite_id) in tiles {
+            self.draw_tile_from((x, y), sheet_id, sprite_id);
+        }
+    }
+
+    /// queues a sprite with its top left corner on a virtual pixel, to be drawn by `present`
+    /// with the others sorted by z. the sprites with a lower z are drawn first, behind the
+    /// others, and the sprites with the same z are drawn in the order they were queued
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a red player, a green tree and a blue rock
+    /// let sheet = RgbaImage::from_fn(24, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([255, 0, 0, 255]),
+    ///     1 => Rgba([0, 255, 0, 255]),
+    ///     _ => Rgba([0, 0, 255, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// // the tree is queued first, but the player walks behind it
+    /// tile_grid.queue_sprite((40, 32), 1, 2);
+    /// tile_grid.queue_sprite((44, 30), 0, 1);
+    /// // with the same z, the rock queued last covers the player
+    /// tile_grid.queue_sprite((48, 30), 2, 1);
+    /// tile_grid.present();
+    ///
+    /// assert_eq!(tile_grid.bitmap().get_pixel((44, 30)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((44, 32)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((48, 30)), 0x0000ff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn queue_sprite(
+        &mut self,
+        (vpixel_x, vpixel_y): (isize, isize),
+        sprite_id: impl SpriteId,
+        z: i32,
+    ) {
+        self.queued_sprites.push(QueuedSprite {
+            z,
+            position: (vpixel_x, vpixel_y),
+            sprite_id: sprite_id.id(),
+        });
+    }
+
+    /// same as `queue_sprite`, but the sprite is placed on a tile
+    pub fn queue_tile(
+        &mut self,
+        (tile_x, tile_y): (isize, isize),
+        sprite_id: impl SpriteId,
+        z: i32,
+    ) {
+        let position = (
+            tile_x.saturating_mul(self.tile_width as isize),
+            tile_y.saturating_mul(self.tile_height as isize),
+        );
+
+        self.queue_sprite(position, sprite_id, z);
+    }
+
+    /// sets the sprite of the cursor, drawn by `present` where `draw_cursor` puts it
+    ///
+    /// # Panics
+    ///
+    /// panics if the sprite is outside the sprite sheet, in debug builds or with the
+    /// `bounds-checks` feature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a green grass sprite and a white cursor
+    /// let sheet = RgbaImage::from_fn(16, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([0, 255, 0, 255]),
+    ///     _ => Rgba([255, 255, 255, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    /// tile_grid.set_cursor(1);
+    ///
+    /// // every frame
+    /// let mouse = (42, 17);
+    /// if let Some(vpixel) = tile_grid.screen_to_vpixel(mouse.into()) {
+    ///     tile_grid.draw_cursor((vpixel.x as isize, vpixel.y as isize));
+    /// }
+    /// // the tile under the mouse is drawn after the cursor, but the cursor stays on top
+    /// tile_grid.draw_tile((5, 2), 0);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel(mouse), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((41, 17)), 0x00ff00);
+    ///
+    /// // the cursor is hidden in a frame without draw_cursor
+    /// tile_grid.draw_tile((5, 2), 0);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel(mouse), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_cursor(&mut self, sprite_id: impl SpriteId) {
+        let sprite_id = sprite_id.id();
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        self.cursor_sprite = Some(sprite_id);
+    }
+
+    /// removes the sprite of the cursor, so it's not drawn anymore
+    pub fn clear_cursor(&mut self) {
+        self.cursor_sprite = None;
+    }
+
+    /// the sprite of the cursor, if one was set
+    pub fn cursor(&self) -> Option<usize> {
+        self.cursor_sprite
+    }
+
+    /// puts the top left corner of the cursor on a virtual pixel for this frame. the cursor is
+    /// drawn by `present` after everything else, so the tiles drawn later in the frame don't
+    /// cover it, and it's hidden in the frames where this is not called
+    pub fn draw_cursor(&mut self, (vpixel_x, vpixel_y): (isize, isize)) {
+        self.cursor_position = Some((vpixel_x, vpixel_y));
+    }
+
+    /// ends the frame, drawing the queued sprites from the lowest z to the highest and emptying
+    /// the queue, then applying the effects of the post pipeline in order, drawing the cursor,
+    /// and filling the letterbox bars with the letterbox color. the cursor is drawn after the
+    /// post pipeline, so the post effects don't change it. call it after drawing the tiles and
+    /// before showing the bitmap
+    ///
+    /// # Panics
+    ///
+    /// panics if a queued sprite is outside the sprite sheet, in debug builds or with the
+    /// `bounds-checks` feature
+    pub fn present(&mut self) {
+        let mut queued_sprites = std::mem::take(&mut self.queued_sprites);
+        queued_sprites.sort_by_key(|sprite| sprite.z);
+
+        for sprite in queued_sprites.drain(..) {
+            self.draw_sprite_at(sprite.position, sprite.sprite_id);
+        }
+
+        // the queue keeps its allocation for the next frame
+        self.queued_sprites = queued_sprites;
+
+        self.pixel_grid.apply_post_effect(&self.post_pipeline);
+
+        if let (Some(sprite_id), Some(position)) = (self.cursor_sprite, self.cursor_position.take())
+        {
+            self.draw_sprite_at(position, sprite_id);
+        }
+
+        if let Some(color) = self.letterbox_color {
+            self.pixel_grid.fill_letterbox(color);
+        }
+    }
+
+    /// draws a panel, like a window or a dialog frame, on a rectangle of tiles. the corners of
+    /// the rectangle get the corner sprites, the borders are covered by repeating the edge
+    /// sprites and the inside by repeating the center one. the part of the panel outside the
+    /// grid is not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, NineSlice, Rect, SpriteSheet, TileGrid};
+    ///
+    /// // a 3x3 sheet, the red grows from left to right and the green from top to bottom
+    /// let sheet = RgbaImage::from_fn(24, 24, |x, y| {
+    ///     Rgba([(x / 8 * 100 + 20) as u8, (y / 8 * 100 + 20) as u8, 0, 255])
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// // the frame is drawn with the sheet laid out like the panel
+    /// let frame = NineSlice {
+    ///     corners: [0, 2, 6, 8],
+    ///     edges: [1, 7, 3, 5],
+    ///     center: 4,
+    /// };
+    /// tile_grid.draw_nine_slice(Rect::new((2, 2), 12, 4), frame);
+    ///
+    /// let tile = |x: usize, y: usize| tile_grid.bitmap().get_pixel((x * 8, y * 8));
+    /// // the corners
+    /// assert_eq!(tile(2, 2), 0x141400);
+    /// assert_eq!(tile(13, 2), 0xdc1400);
+    /// assert_eq!(tile(2, 5), 0x14dc00);
+    /// assert_eq!(tile(13, 5), 0xdcdc00);
+    /// // the edges, repeated along the borders
+    /// assert_eq!(tile(3, 2), 0x781400);
+    /// assert_eq!(tile(12, 5), 0x78dc00);
+    /// assert_eq!(tile(2, 4), 0x147800);
+    /// assert_eq!(tile(13, 3), 0xdc7800);
+    /// // the center
+    /// assert_eq!(tile(7, 3), 0x787800);
+    /// // nothing outside the panel
+    /// assert_eq!(tile(14, 2), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_nine_slice(&mut self, rect: Rect, nine_slice: NineSlice) {
+        if rect.is_empty() {
+            return;
+        }
+
+        let NineSlice {
+            corners: [top_left, top_right, bottom_left, bottom_right],
+            edges: [top, bottom, left, right],
+            center,
+        } = nine_slice;
+        let (last_x, last_y) = (rect.right() - 1, rect.bottom() - 1);
+
+        for tile_y in rect.y..rect.bottom() {
+            for tile_x in rect.x..rect.right() {
+                let sprite_id = match (tile_x, tile_y) {
+                    (x, y) if x == rect.x && y == rect.y => top_left,
+                    (x, y) if x == last_x && y == rect.y => top_right,
+                    (x, y) if x == rect.x && y == last_y => bottom_left,
+                    (x, y) if x == last_x && y == last_y => bottom_right,
+                    (_, y) if y == rect.y => top,
+                    (_, y) if y == last_y => bottom,
+                    (x, _) if x == rect.x => left,
+                    (x, _) if x == last_x => right,
+                    _ => center,
+                };
+
+                self.draw_tile_signed((tile_x, tile_y), sprite_id);
+            }
+        }
+    }
+
+    /// replaces the palette the sprites of an `IndexedSpriteSheet` are drawn with, changing it
+    /// between frames recolors everything drawn from indexed sprites at once. the colors are
+    /// `0xRRGGBB` and the first one is never used, it's the transparent index
+    pub fn set_palette(&mut self, palette: [u32; 256]) {
+        *self.palette = palette;
+    }
+
+    pub fn palette(&self) -> &[u32; 256] {
+        &self.palette
+    }
+
+    /// the palette, to change only some of its colors, for example during a fade
+    pub fn palette_mut(&mut self) -> &mut [u32; 256] {
+        &mut self.palette
+    }
+
+    /// same as `draw_tile`, but with a sprite of an indexed sheet, colored with the palette of
+    /// the tile grid. the pixels with the transparent index 0 are not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, IndexedSpriteSheet, SpriteSheet, TileGrid};
+    ///
+    /// let mut palette = [0; 256];
+    /// palette[1] = 0xff0000;
+    /// let image = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let indexed = IndexedSpriteSheet::from_image(image, 8, &palette)?;
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    /// tile_grid.set_palette(palette);
+    /// tile_grid.draw_tile_indexed((0, 0), &indexed, 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xff0000);
+    ///
+    /// // the same sprite in another color, without converting the sheet again
+    /// tile_grid.palette_mut()[1] = 0x0000ff;
+    /// tile_grid.draw_tile_indexed((0, 0), &indexed, 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x0000ff);
+    ///
+    /// // the transparent index keeps the background, even when every alpha is drawn
+    /// let mut image = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// image.put_pixel(0, 0, Rgba([0, 0, 0, 0]));
+    /// let indexed = IndexedSpriteSheet::from_image(image, 8, &palette)?;
+    ///
+    /// tile_grid.pixel_grid_mut().fill(0x00ff00);
+    /// tile_grid.pixel_grid_mut().set_alpha_threshold(0);
+    /// tile_grid.draw_tile_indexed((0, 0), &indexed, 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((1, 0)), 0x0000ff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_indexed(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_sheet: &IndexedSpriteSheet,
+        sprite_id: impl SpriteId,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+        check!(
+            sprite_id < sprite_sheet.sprite_count(),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        let sprite_dimensions = (sprite_sheet.sprite_width(), sprite_sheet.sprite_height());
+        let palette = &self.palette;
+
+        self.pixel_grid.draw_pixels_scaled_unchecked(
+            (pixel_x, pixel_y),
+            sprite_dimensions,
+            (self.tile_width, self.tile_height),
+            // mixed by alpha, so the transparent index is skipped whatever the alpha threshold
+            Coverage {
+                opacity: u8::MAX,
+                by_alpha: true,
+            },
+            |x, y| match sprite_sheet.index(sprite_id, (x, y)) {
+                0 => 0,
+                index => 0xff000000 | palette[index as usize] & 0xffffff,
+            },
+        );
+    }
+
+    /// same as `draw_tile`, but the arguments are never checked in release builds, even with the
+    /// `bounds-checks` feature enabled.
+    /// only use it with tile coordinates and sprite ids that are known to be valid, otherwise the
+    /// sprite will be drawn in the wrong place or the call will panic
+    pub fn draw_tile_unchecked(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+    ) {
+        let sprite_id = sprite_id.id();
+        debug_assert!(tile_x < self.width);
+        debug_assert!(tile_y < self.height);
+
+        // virtual pixel coordinates
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+
+        self.pixel_grid.draw_sprite_unchecked(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+    }
+
+    /// number of sprites in the sprite sheet, valid sprite ids go from 0 to `sprite_count() - 1`
+    pub fn sprite_count(&self) -> usize {
+        self.sprite_sheet.sprite_count()
+    }
+
+    /// checks if the sprite id maps to a sprite inside the sprite sheet, useful to validate tile
+    /// data before drawing it
+    pub fn sprite_exists(&self, sprite_id: impl SpriteId) -> bool {
+        let sprite_id = sprite_id.id();
+        self.sprite_sheet.contains(sprite_id)
+    }
+
+    /// the properties of the sprite with the given id, set in the manifest of the sheet or with
+    /// `SpriteSheet::set_sprite_meta`. it's `None` if the sprite has no properties or if the id
+    /// maps to a sprite outside the sprite sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, MetaValue, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.sprite_sheet_mut().set_sprite_meta((2, 0), "solid", MetaValue::Bool(true))?;
+    ///
+    /// let solid = |tile_grid: &TileGrid, sprite_id| {
+    ///     tile_grid
+    ///         .sprite_meta(sprite_id)
+    ///         .and_then(|meta| meta.get("solid")?.as_bool())
+    ///         .unwrap_or(false)
+    /// };
+    /// assert!(solid(&tile_grid, 2));
+    /// assert!(!solid(&tile_grid, 0));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn sprite_meta(&self, sprite_id: impl SpriteId) -> Option<&SpriteMeta> {
+        let sprite_id = sprite_id.id();
+        if !self.sprite_sheet.contains(sprite_id) {
+            return None;
+        }
+
+        self.sprite_sheet
+            .sprite_meta(self.sprite_sheet.id_to_coords(sprite_id))
+    }
+
+    /// returns the image of the sprite with the given id, or `None` if the id maps to a sprite
+    /// outside the sprite sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// let sprite_count = tile_grid.sprite_count();
+    /// assert!(tile_grid.try_sprite(sprite_count - 1).is_some());
+    /// assert!(tile_grid.try_sprite(sprite_count).is_none());
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn try_sprite(&self, sprite_id: impl SpriteId) -> Option<SubImage<&RgbaImage>> {
+        let sprite_id = sprite_id.id();
+        self.sprite_sheet
+            .contains(sprite_id)
+            .then(|| self.sprite_sheet.sprite(sprite_id))
+    }
+
+    /// same as `draw_tile`, but the tile coordinates can be outside the grid, in which case
+    /// nothing is drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the tile is to the right of the grid, so it's not drawn
+    /// tile_grid.draw_tile_clipped((tile_grid.dimensions().0, 0), 0);
+    /// assert!(tile_grid.bitmap().as_slice().iter().all(|&pixel| pixel == 0));
+    ///
+    /// // the last column is still drawn
+    /// tile_grid.draw_tile_clipped((19, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((19 * 8, 0)), 0xff0000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_clipped(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+    ) {
+        let sprite_id = sprite_id.id();
+        let tile_x = tile_x.min(isize::MAX as usize) as isize;
+        let tile_y = tile_y.min(isize::MAX as usize) as isize;
+
+        self.draw_tile_signed((tile_x, tile_y), sprite_id);
+    }
+
+    /// same as `draw_tile_clipped`, but with signed tile coordinates, so tiles that scrolled past
+    /// the top or left border are culled too
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // the tile is above the grid, so it's not drawn
+    /// tile_grid.draw_tile_signed((0, -1), 0);
+    /// assert!(tile_grid.bitmap().as_slice().iter().all(|&pixel| pixel == 0));
+    ///
+    /// tile_grid.draw_tile_signed((0, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xff0000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_signed(&mut self, (tile_x, tile_y): (isize, isize), sprite_id: impl SpriteId) {
+        let sprite_id = sprite_id.id();
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        // virtual pixel coordinates
+        let (pixel_x, pixel_y) = (
+            tile_x.saturating_mul(self.tile_width as isize),
+            tile_y.saturating_mul(self.tile_height as isize),
+        );
+
+        self.pixel_grid.draw_sprite_clipped(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+    }
+
+    /// draws a sprite with its top left corner on a virtual pixel, not snapped to the tiles, so
+    /// entities can move smoothly. the sprite is scaled to the tile size like in `draw_tile`, and
+    /// the part of it outside the grid is not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// // a sprite walking one virtual pixel per frame, even across the tiles
+    /// let frame_counter = 3;
+    /// tile_grid.draw_sprite_at((frame_counter, 13), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((2, 13)), 0x000000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 13)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((10, 20)), 0xff0000);
+    ///
+    /// // half of the sprite is past the left border
+    /// tile_grid.draw_sprite_at((-4, 0), 0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 0)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4, 0)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_sprite_at(
+        &mut self,
+        (vpixel_x, vpixel_y): (isize, isize),
+        sprite_id: impl SpriteId,
+    ) {
+        self.draw_sprite_anchored((vpixel_x, vpixel_y), sprite_id, Anchor::TopLeft);
+    }
+
+    /// same as `draw_sprite_at`, but the virtual pixel is where the anchor of the sprite goes,
+    /// for example the bottom center for the feet of a character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Anchor, Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let character = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(character, 8)?)?;
+    ///
+    /// // the character stands on the bottom of the tile (3, 4), between its columns 3 and 4
+    /// let feet = (3 * 8 + 4, 5 * 8);
+    /// tile_grid.draw_sprite_anchored(feet, 0, Anchor::BottomCenter);
+    ///
+    /// // so the sprite covers the tile (3, 4)
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((4 * 8 - 1, 5 * 8 - 1)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8 - 1, 4 * 8)), 0x000000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 5 * 8)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_sprite_anchored(
+        &mut self,
+        (vpixel_x, vpixel_y): (isize, isize),
+        sprite_id: impl SpriteId,
+        anchor: Anchor,
+    ) {
+        let sprite_id = sprite_id.id();
+        check!(
+            self.sprite_sheet.contains(sprite_id),
+            "sprite {sprite_id} is outside the sprite sheet"
+        );
+
+        let (anchor_x, anchor_y) = anchor.offset((self.tile_width, self.tile_height));
+
+        self.pixel_grid.draw_sprite_clipped(
+            (
+                vpixel_x.saturating_sub(anchor_x),
+                vpixel_y.saturating_sub(anchor_y),
+            ),
+            &self.sprite_sheet,
+            self.sprite_sheet.id_to_coords(sprite_id),
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+    }
+
+    /// draws a tile in the tile coordinates, using the sprite with the given name
+    ///
+    /// # Errors
+    ///
+    /// fails if no sprite has the name or if the tile coordinates are outside the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a blue water sprite and a green grass one
+    /// let sheet = RgbaImage::from_fn(16, 8, |x, _| match x / 8 {
+    ///     0 => Rgba([0, 0, 255, 255]),
+    ///     _ => Rgba([0, 255, 0, 255]),
+    /// });
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// tile_grid.sprite_sheet_mut().name_sprite("grass", (1, 0))?;
+    /// tile_grid.draw_tile_named((3, 4), "grass")?;
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x00ff00);
+    ///
+    /// assert!(tile_grid.draw_tile_named((3, 4), "lava").is_err());
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_named(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        name: &str,
+    ) -> Result<(), TileyError> {
+        let coords = self
+            .sprite_sheet
+            .sprite_coords(name)
+            .ok_or_else(|| TileyError::UnknownSpriteName(name.to_string()))?;
+
+        if !self.bounds().contains((tile_x, tile_y)) {
+            return Err(TileyError::TileOutOfBounds {
+                position: (tile_x, tile_y),
+                dimensions: (self.width, self.height),
+            });
+        }
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        self.pixel_grid.draw_sprite_unchecked(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            coords,
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+
+        Ok(())
+    }
+
+    /// draws a tile in the tile coordinates, using a frame of the animation with the given name.
+    /// the frame index wraps around, so a frame counter can be passed directly
+    ///
+    /// # Errors
+    ///
+    /// fails if no animation has the name or if the tile coordinates are outside the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// // a row of 4 sprites, redder from left to right
+    /// let sheet = RgbaImage::from_fn(32, 8, |x, _| Rgba([(x / 8 * 64 + 32) as u8, 0, 0, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sheet, 8)?)?;
+    ///
+    /// tile_grid.sprite_sheet_mut().add_animation("water", vec![(1, 0), (2, 0), (3, 0)])?;
+    ///
+    /// let frame_counter = 25;
+    /// tile_grid.draw_animation_frame((3, 4), "water", frame_counter / 10)?;
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0xe00000);
+    ///
+    /// // the fourth frame is the first one again
+    /// tile_grid.draw_animation_frame((3, 4), "water", 3)?;
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3 * 8, 4 * 8)), 0x600000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_animation_frame(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        name: &str,
+        frame: usize,
+    ) -> Result<(), TileyError> {
+        let frames = self
+            .sprite_sheet
+            .animation(name)
+            .ok_or_else(|| TileyError::UnknownSpriteName(name.to_string()))?;
+        let coords = frames[frame % frames.len()];
+
+        if !self.bounds().contains((tile_x, tile_y)) {
+            return Err(TileyError::TileOutOfBounds {
+                position: (tile_x, tile_y),
+                dimensions: (self.width, self.height),
+            });
+        }
+
+        let (pixel_x, pixel_y) = (tile_x * self.tile_width, tile_y * self.tile_height);
+        self.pixel_grid.draw_sprite_unchecked(
+            (pixel_x, pixel_y),
+            &self.sprite_sheet,
+            coords,
+            (self.tile_width, self.tile_height),
+            SpriteTransform::default(),
+        );
+
+        Ok(())
+    }
+
+    /// same as `draw_tile`, but returns an error instead of panicking when the tile coordinates
+    /// are outside the grid or the sprite id is outside the sprite sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid, TileyError};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// let result = tile_grid.try_draw_tile((100, 0), 0);
+    /// assert!(matches!(result, Err(TileyError::TileOutOfBounds { .. })));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn try_draw_tile(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+    ) -> Result<(), TileyError> {
+        let sprite_id = sprite_id.id();
+        if !self.bounds().contains((tile_x, tile_y)) {
+            return Err(TileyError::TileOutOfBounds {
+                position: (tile_x, tile_y),
+                dimensions: (self.width, self.height),
+            });
+        }
+
+        if !self.sprite_sheet.contains(sprite_id) {
+            return Err(TileyError::SpriteOutOfBounds { sprite_id });
+        }
+
+        self.draw_tile_unchecked((tile_x, tile_y), sprite_id);
+        Ok(())
+    }
+}
+
+impl TileGridBuilder {
+    /// starts building a tile grid with the given dimensions in tiles, the tile dimensions
+    /// default to the sprite dimensions of a loaded sheet, or to 8x8 for a sheet loaded from a
+    /// path
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::{Bitmap, TileGridBuilder};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let tile_grid = TileGridBuilder::new(20, 30)
+    ///     .tile_size(8)
+    ///     .sprite_sheet("./resources/sprite_sheet.png")
+    ///     .letterbox_color(0x101010)
+    ///     .build(bitmap)?;
+    /// ```
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            tile_dimensions: None,
+            sprite_sheet: None,
+            letterbox_color: None,
+            pixel_format: None,
+            sheet_layout: None,
+            pixel_aspect: None,
+            scale_mode: None,
+            alignment: None,
+        }
+    }
+
+    /// side length of a tile, in virtual pixels
+    pub fn tile_size(mut self, tile_size: usize) -> Self {
+        self.tile_dimensions = Some((tile_size, tile_size));
+        self
+    }
+
+    /// width and height of a rectangular tile, in virtual pixels. the sprites of a sheet loaded
+    /// from a path are sliced with the same dimensions
+    pub fn tile_dimensions(mut self, tile_width: usize, tile_height: usize) -> Self {
+        self.tile_dimensions = Some((tile_width, tile_height));
+        self
+    }
+
+    /// path of the sprite sheet the tiles are drawn from, it's loaded when the grid is built.
+    /// a sprite sheet is required, either from this or from `loaded_sprite_sheet`
+    pub fn sprite_sheet(mut self, path: impl AsRef<Path>) -> Self {
+        self.sprite_sheet = Some(SpriteSheetSource::Path(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// an already loaded sprite sheet the tiles are drawn from, its sprites are scaled if they
+    /// are not as big as the tiles
+    pub fn loaded_sprite_sheet(mut self, sprite_sheet: SpriteSheet) -> Self {
+        self.sprite_sheet = Some(SpriteSheetSource::Loaded(Box::new(sprite_sheet)));
+        self
+    }
+
+    /// color of the bars left around the pixel grid by the clamping, they are filled when the
+    /// grid is built and at the end of every frame, see `TileGrid::set_letterbox_color`. if it's
+    /// not set, the bars keep the previous content of the bitmap
+    pub fn letterbox_color(mut self, color: impl Into<Color>) -> Self {
+        let color = u32::from(color.into());
+        self.letterbox_color = Some(color);
+        self
+    }
+
+    /// format the colors are packed in when they are drawn on the bitmap, if it's not set the
+    /// format of the bitmap is kept
+    pub fn pixel_format(mut self, format: PixelFormat) -> Self {
+        self.pixel_format = Some(format);
+        self
+    }
+
+    /// how the sprite ids are mapped to the sprites of the sheet, if it's not set the layout of
+    /// the sheet is kept
+    pub fn sheet_layout(mut self, layout: SheetLayout) -> Self {
+        self.sheet_layout = Some(layout);
+        self
+    }
+
+    /// width of a virtual pixel divided by its height, see `PixelGrid::set_pixel_aspect`. if
+    /// it's not set the pixels are square
+    pub fn pixel_aspect(mut self, aspect: f64) -> Self {
+        self.pixel_aspect = Some(aspect);
+        self
+    }
+
+    /// how the virtual pixels are scaled to fill the bitmap, see `PixelGrid::set_scale_mode`.
+    /// if it's not set the grid is as big as the bitmap allows
+    pub fn scale_mode(mut self, scale_mode: ScaleMode) -> Self {
+        self.scale_mode = Some(scale_mode);
+        self
+    }
+
+    /// the point of the bitmap the grid is placed on, see `PixelGrid::set_alignment`. if it's
+    /// not set the grid is centered
+    pub fn alignment(mut self, alignment: Anchor) -> Self {
+        self.alignment = Some(alignment);
+        self
+    }
+
+    /// builds the tile grid on top of the bitmap
+    ///
+    /// # Errors
+    ///
+    /// same as `TileGrid::new` and `PixelGrid::set_pixel_aspect`, and fails with
+    /// `TileyError::MissingSpriteSheet` if no sprite sheet was set
+    pub fn build<S: PixelStorage>(self, bitmap: Bitmap<S>) -> Result<TileGrid<S>, TileyError> {
+        let (tile_dimensions, sprite_sheet) = match self.sprite_sheet {
+            Some(SpriteSheetSource::Path(path)) => {
+                let tile_dimensions = self.tile_dimensions.unwrap_or((8, 8));
+                let image = ImageReader::open(&path)?.decode()?;
+                let sprite_sheet =
+                    SpriteSheet::from_image_with_dimensions(image, tile_dimensions, 0, 0)?
+                        .with_source(SheetSource::new(path, SourceFormat::Image));
+                (tile_dimensions, sprite_sheet)
+            }
+            Some(SpriteSheetSource::Loaded(sprite_sheet)) => {
+                let tile_dimensions = self
+                    .tile_dimensions
+                    .unwrap_or((sprite_sheet.sprite_width(), sprite_sheet.sprite_height()));
+                (tile_dimensions, *sprite_sheet)
+            }
+            None => return Err(TileyError::MissingSpriteSheet),
+        };
+        let sprite_sheet = match self.sheet_layout {
+            Some(layout) => sprite_sheet.with_layout(layout),
+            None => sprite_sheet,
+        };
+
+        let bitmap = match self.pixel_format {
+            Some(format) => bitmap.with_pixel_format(format),
+            None => bitmap,
+        };
+
+        let mut tile_grid = TileGrid::with_tile_dimensions(
+            bitmap,
+            self.width,
+            self.height,
+            tile_dimensions,
+            sprite_sheet,
+        )?;
+
+        if let Some(aspect) = self.pixel_aspect {
+            tile_grid.pixel_grid.set_pixel_aspect(aspect)?;
+        }
+
+        if let Some(scale_mode) = self.scale_mode {
+            tile_grid.pixel_grid.set_scale_mode(scale_mode);
+        }
+
+        if let Some(alignment) = self.alignment {
+            tile_grid.pixel_grid.set_alignment(alignment);
+        }
+
+        if let Some(color) = self.letterbox_color {
+            tile_grid.set_letterbox_color(color);
+        }
 
-        self.pixel_grid.draw_image((pixel_x, pixel_y), sprite);
+        Ok(tile_grid)
     }
 }
@@ -0,0 +1,165 @@
+//! loading of sprite sheets described by a manifest file, to keep the asset metadata out of the
+//! code
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use image::ImageReader;
+use serde::Deserialize;
+
+use crate::{SheetLayout, SheetSource, SourceFormat, SpriteMeta, SpriteSheet, TileyError};
+
+/// the content of a manifest file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    /// path of the sheet image, relative to the manifest
+    image: PathBuf,
+    sprite_size: usize,
+    #[serde(default)]
+    margin: usize,
+    #[serde(default)]
+    spacing: usize,
+    #[serde(default)]
+    layout: ManifestLayout,
+    /// `0xRRGGBB` color drawn as transparent
+    color_key: Option<u32>,
+    /// sprite names with their (column, row) coordinates
+    #[serde(default)]
+    sprites: HashMap<String, [usize; 2]>,
+    #[serde(default)]
+    animations: HashMap<String, Vec<Frame>>,
+    /// properties of the named sprites, by sprite name
+    #[serde(default)]
+    meta: HashMap<String, SpriteMeta>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+enum ManifestLayout {
+    #[default]
+    RowMajor,
+    SingleRow,
+}
+
+/// a frame of an animation, either by coordinates or by sprite name
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Frame {
+    Coords([usize; 2]),
+    Name(String),
+}
+
+impl SpriteSheet {
+    /// loads a sprite sheet described by a manifest file. the manifest is parsed as json if the
+    /// file has a `.json` extension, and as toml otherwise
+    ///
+    /// ```toml
+    /// # path of the sheet image, relative to the manifest
+    /// image = "tiles.png"
+    /// sprite_size = 8
+    /// # optional, border around the sprites and gap between them, in pixels
+    /// margin = 1
+    /// spacing = 2
+    /// # optional, "row-major" or "single-row"
+    /// layout = "row-major"
+    /// # optional, color of the pixels that are not drawn
+    /// color_key = 0xff00ff
+    ///
+    /// # optional, sprite names with their [column, row] coordinates
+    /// [sprites]
+    /// grass = [0, 0]
+    /// water_0 = [1, 0]
+    ///
+    /// # optional, frames can be given by coordinates or by sprite name
+    /// [animations]
+    /// water = ["water_0", [2, 0], [3, 0]]
+    ///
+    /// # optional, properties of the named sprites, read with `TileGrid::sprite_meta`
+    /// [meta.grass]
+    /// solid = false
+    /// [meta.water_0]
+    /// solid = true
+    /// damage = 3
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// fails if the manifest can't be read or parsed, if the image can't be loaded, if a named
+    /// sprite or an animation frame is outside the sheet, or if the metadata is for a sprite
+    /// without a name
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// let sprite_sheet = SpriteSheet::from_manifest("./resources/tiles.toml")?;
+    /// ```
+    pub fn from_manifest(path: impl AsRef<Path>) -> Result<Self, TileyError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+
+        let manifest: Manifest = match path.extension().is_some_and(|e| e == "json") {
+            true => {
+                serde_json::from_str(&content).map_err(|e| TileyError::Manifest(e.to_string()))?
+            }
+            false => toml::from_str(&content).map_err(|e| TileyError::Manifest(e.to_string()))?,
+        };
+
+        let image_path = path.parent().unwrap_or(Path::new("")).join(&manifest.image);
+        let layout = match manifest.layout {
+            ManifestLayout::RowMajor => SheetLayout::RowMajor,
+            ManifestLayout::SingleRow => SheetLayout::SingleRow,
+        };
+
+        let image = ImageReader::open(&image_path)?.decode()?;
+        let mut sprite_sheet = SpriteSheet::from_image_with_spacing(
+            image,
+            manifest.sprite_size,
+            manifest.margin,
+            manifest.spacing,
+        )?
+        .with_layout(layout)
+        .with_source(SheetSource::new(image_path, SourceFormat::Image));
+
+        if let Some(color) = manifest.color_key {
+            sprite_sheet = sprite_sheet.with_color_key(color);
+        }
+
+        for (name, [x, y]) in manifest.sprites {
+            sprite_sheet.name_sprite(name, (x, y))?;
+        }
+
+        for (name, frames) in manifest.animations {
+            let frames = frames
+                .into_iter()
+                .map(|frame| match frame {
+                    Frame::Coords([x, y]) => Ok((x, y)),
+                    Frame::Name(sprite) => sprite_sheet.sprite_coords(&sprite).ok_or_else(|| {
+                        TileyError::Manifest(format!(
+                            "the animation {name:?} uses the unknown sprite {sprite:?}"
+                        ))
+                    }),
+                })
+                .collect::<Result<_, _>>()?;
+
+            sprite_sheet.add_animation(name, frames)?;
+        }
+
+        for (name, properties) in manifest.meta {
+            let coords = sprite_sheet.sprite_coords(&name).ok_or_else(|| {
+                TileyError::Manifest(format!("the metadata is for the unknown sprite {name:?}"))
+            })?;
+
+            for (key, value) in properties {
+                sprite_sheet.set_sprite_meta(coords, key, value)?;
+            }
+        }
+
+        Ok(sprite_sheet)
+    }
+}
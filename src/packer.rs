@@ -0,0 +1,166 @@
+//! packing of separate sprite images in a single atlas at runtime, so small projects don't need
+//! to stitch a sprite sheet by hand
+
+use std::{collections::HashSet, fs, path::Path};
+
+use image::{imageops, ImageFormat, ImageReader, RgbaImage};
+
+use crate::{Rect, SpriteSheet, TileyError};
+
+impl SpriteSheet {
+    /// packs separate sprite images in a single atlas. the sprites get the ids in the order they
+    /// are given, and they can also be drawn by name
+    ///
+    /// # Errors
+    ///
+    /// fails if there are no sprites, if a sprite is empty, or with
+    /// `TileyError::DuplicateSpriteName` if two sprites have the same name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::RgbaImage;
+    /// use tiley::{SpriteSheet, TileyError};
+    ///
+    /// let sprite_sheet = SpriteSheet::pack([
+    ///     ("player", RgbaImage::new(16, 16)),
+    ///     ("enemy", RgbaImage::new(16, 16)),
+    ///     ("boss", RgbaImage::new(32, 32)),
+    /// ])?;
+    /// assert_eq!(sprite_sheet.sprite_count(), 3);
+    /// assert_eq!(sprite_sheet.sprite_coords("enemy"), Some((1, 0)));
+    ///
+    /// let twice = SpriteSheet::pack([
+    ///     ("player", RgbaImage::new(16, 16)),
+    ///     ("player", RgbaImage::new(16, 16)),
+    /// ]);
+    /// assert!(matches!(twice, Err(TileyError::DuplicateSpriteName(_))));
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn pack<N: Into<String>>(
+        sprites: impl IntoIterator<Item = (N, RgbaImage)>,
+    ) -> Result<Self, TileyError> {
+        let sprites: Vec<(String, RgbaImage)> = sprites
+            .into_iter()
+            .map(|(name, image)| (name.into(), image))
+            .collect();
+
+        if let Some((name, _)) = sprites
+            .iter()
+            .find(|(_, image)| image.width() == 0 || image.height() == 0)
+        {
+            return Err(TileyError::BadDimensions(format!(
+                "the sprite {name:?} is empty"
+            )));
+        }
+
+        let mut seen = HashSet::new();
+        if let Some((name, _)) = sprites.iter().find(|(name, _)| !seen.insert(name.as_str())) {
+            return Err(TileyError::DuplicateSpriteName(name.clone()));
+        }
+
+        let regions = pack_regions(&sprites);
+
+        let width = regions
+            .iter()
+            .map(|region| region.right())
+            .max()
+            .unwrap_or(0);
+        let height = regions
+            .iter()
+            .map(|region| region.bottom())
+            .max()
+            .unwrap_or(0);
+        let mut atlas = RgbaImage::new(width as u32, height as u32);
+        for ((_, image), region) in sprites.iter().zip(&regions) {
+            imageops::replace(&mut atlas, image, region.x as i64, region.y as i64);
+        }
+
+        let mut sprite_sheet = SpriteSheet::from_atlas(atlas, regions)?;
+        for (id, (name, _)) in sprites.into_iter().enumerate() {
+            sprite_sheet.name_sprite(name, (id, 0))?;
+        }
+
+        Ok(sprite_sheet)
+    }
+
+    /// same as `pack`, but with every image file in a folder, the sprites are named after the
+    /// file names without the extension and get the ids in alphabetical order
+    ///
+    /// # Errors
+    ///
+    /// fails if the folder can't be read, if an image can't be decoded, if the folder has no
+    /// images, or with `TileyError::DuplicateSpriteName` if two images have the same name with
+    /// different extensions, like `player.png` and `player.gif`
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use tiley::SpriteSheet;
+    ///
+    /// let sprite_sheet = SpriteSheet::pack_dir("./resources/sprites")?;
+    /// tile_grid.set_sprite_sheet(sprite_sheet);
+    /// tile_grid.draw_tile_named((3, 4), "player")?;
+    /// ```
+    pub fn pack_dir(path: impl AsRef<Path>) -> Result<Self, TileyError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.is_file() && ImageFormat::from_path(&path).is_ok() {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let sprites = paths
+            .into_iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                Ok((name, ImageReader::open(&path)?.decode()?.into_rgba8()))
+            })
+            .collect::<Result<Vec<_>, TileyError>>()?;
+
+        Self::pack(sprites)
+    }
+}
+
+/// places the sprites on shelves, the tallest first, in an atlas about as wide as it's tall
+fn pack_regions(sprites: &[(String, RgbaImage)]) -> Vec<Rect> {
+    let area: u64 = sprites
+        .iter()
+        .map(|(_, image)| image.width() as u64 * image.height() as u64)
+        .sum();
+    let widest = sprites
+        .iter()
+        .map(|(_, image)| image.width())
+        .max()
+        .unwrap_or(0);
+    let atlas_width = (area as f64).sqrt().ceil().max(widest as f64) as usize;
+
+    let mut order: Vec<usize> = (0..sprites.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(sprites[i].1.height()));
+
+    let mut regions = vec![Rect::default(); sprites.len()];
+    let (mut shelf_x, mut shelf_y, mut shelf_height) = (0, 0, 0);
+    for i in order {
+        let (width, height) = (
+            sprites[i].1.width() as usize,
+            sprites[i].1.height() as usize,
+        );
+
+        // start a new shelf when the sprite doesn't fit on the current one
+        if shelf_x + width > atlas_width {
+            (shelf_x, shelf_y, shelf_height) = (0, shelf_y + shelf_height, 0);
+        }
+
+        regions[i] = Rect::new((shelf_x as isize, shelf_y as isize), width, height);
+        shelf_x += width;
+        shelf_height = shelf_height.max(height);
+    }
+
+    regions
+}
@@ -0,0 +1,222 @@
+//! recoloring of sprites when they are drawn, so one sprite can be reused for variants with
+//! different colors
+
+use std::collections::HashMap;
+
+use crate::{PixelStorage, SpriteId, SpriteTransform, TileGrid};
+
+/// colors of a sprite replaced by other colors when it's drawn, like the enemies of retro games
+/// that share their art with a different palette. the colors are `0xRRGGBB`, the alpha of the
+/// pixels is kept and the colors that are not in the map are drawn as they are
+///
+/// # Examples
+///
+/// ```
+/// use tiley::PaletteMap;
+///
+/// // the red enemy becomes blue, with a darker blue for the shading
+/// let blue_enemy = PaletteMap::new()
+///     .with(0xff0000, 0x0000ff)
+///     .with(0x800000, 0x000080);
+/// assert_eq!(blue_enemy.get(0xff0000), Some(0x0000ff));
+/// assert_eq!(blue_enemy.get(0xffffff), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaletteMap {
+    colors: HashMap<u32, u32>,
+}
+
+/// a set of colors rotated over time, for the water and lava of classic games. the tiles drawn
+/// with `TileGrid::draw_tile_cycled` are remembered, and after every `advance` only they are
+/// redrawn by `TileGrid::redraw_cycled`, instead of the whole screen
+///
+/// # Examples
+///
+/// ```
+/// use image::{Rgba, RgbaImage};
+/// use tiley::{Bitmap, PaletteCycle, SpriteSheet, TileGrid};
+///
+/// // water with three shades of blue
+/// let mut water = RgbaImage::from_pixel(8, 8, Rgba([0, 0, 128, 255]));
+/// water.put_pixel(1, 1, Rgba([0, 0, 192, 255]));
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(water, 8)?)?;
+///
+/// let mut cycle = PaletteCycle::new([0x000080, 0x0000c0, 0x0000ff]);
+/// tile_grid.draw_tile_cycled((0, 0), 0, &mut cycle);
+/// assert_eq!(tile_grid.bitmap().get_pixel((1, 1)), 0x0000c0);
+///
+/// // every tick
+/// cycle.advance();
+/// tile_grid.redraw_cycled(&cycle);
+/// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x0000c0);
+/// assert_eq!(tile_grid.bitmap().get_pixel((1, 1)), 0x0000ff);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaletteCycle {
+    /// the `0xRRGGBB` colors, each one is replaced by the one `offset` places after it
+    colors: Vec<u32>,
+    offset: usize,
+    /// the sprite ids of the tiles drawn with the cycle, by tile position
+    tiles: HashMap<(usize, usize), usize>,
+}
+
+impl PaletteMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// same as `insert`, but taking and returning the map, to build it in one expression
+    pub fn with(mut self, from: u32, to: u32) -> Self {
+        self.insert(from, to);
+        self
+    }
+
+    /// replaces the color `from` with the color `to`, or changes its replacement if it was
+    /// already replaced
+    pub fn insert(&mut self, from: u32, to: u32) {
+        self.colors.insert(from & 0xffffff, to & 0xffffff);
+    }
+
+    /// stops replacing a color, returning its replacement if it had one
+    pub fn remove(&mut self, from: u32) -> Option<u32> {
+        self.colors.remove(&(from & 0xffffff))
+    }
+
+    /// the replacement of a color, if it has one
+    pub fn get(&self, color: u32) -> Option<u32> {
+        self.colors.get(&(color & 0xffffff)).copied()
+    }
+
+    /// number of replaced colors
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// a `0xAARRGGBB` pixel with its color replaced, keeping the alpha
+    fn map(&self, argb: u32) -> u32 {
+        match self.get(argb) {
+            Some(color) => argb & 0xff000000 | color,
+            None => argb,
+        }
+    }
+}
+
+impl PaletteCycle {
+    /// a cycle of `0xRRGGBB` colors, after every step each color is drawn as the one after it
+    /// and the last one as the first
+    pub fn new(colors: impl IntoIterator<Item = u32>) -> Self {
+        PaletteCycle {
+            colors: colors.into_iter().map(|color| color & 0xffffff).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// rotates the colors by one step
+    pub fn advance(&mut self) {
+        self.set_offset(self.offset + 1);
+    }
+
+    /// how many steps the colors are rotated by, always less than the number of colors
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// rotates the colors by a number of steps from the start of the cycle, to follow a timer
+    pub fn set_offset(&mut self, offset: usize) {
+        self.offset = offset.checked_rem(self.colors.len()).unwrap_or(0);
+    }
+
+    /// the colors replaced at the current step of the cycle
+    pub fn palette(&self) -> PaletteMap {
+        let count = self.colors.len();
+        (0..count)
+            .map(|i| (self.colors[i], self.colors[(i + self.offset) % count]))
+            .collect()
+    }
+
+    /// stops redrawing the tile at a position, when something else is drawn on it
+    pub fn forget_tile(&mut self, (tile_x, tile_y): (usize, usize)) {
+        self.tiles.remove(&(tile_x, tile_y));
+    }
+
+    /// stops redrawing every tile
+    pub fn clear_tiles(&mut self) {
+        self.tiles.clear();
+    }
+}
+
+impl FromIterator<(u32, u32)> for PaletteMap {
+    fn from_iter<I: IntoIterator<Item = (u32, u32)>>(colors: I) -> Self {
+        let mut palette = PaletteMap::new();
+        for (from, to) in colors {
+            palette.insert(from, to);
+        }
+
+        palette
+    }
+}
+
+impl<S: PixelStorage> TileGrid<S> {
+    /// same as `draw_tile`, but the colors of the sprite are replaced by the palette map
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use image::{Rgba, RgbaImage};
+    /// use tiley::{Bitmap, PaletteMap, SpriteSheet, TileGrid};
+    ///
+    /// // a red sprite with a white eye
+    /// let mut sprite = RgbaImage::from_pixel(8, 8, Rgba([255, 0, 0, 255]));
+    /// sprite.put_pixel(2, 2, Rgba([255, 255, 255, 255]));
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, SpriteSheet::from_image(sprite, 8)?)?;
+    ///
+    /// let green = PaletteMap::from_iter([(0xff0000, 0x00ff00)]);
+    /// tile_grid.draw_tile_palette((0, 0), 0, &green);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((2, 2)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_tile_palette(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        palette: &PaletteMap,
+    ) {
+        let transform = SpriteTransform::default();
+        self.draw_tile_mapped((tile_x, tile_y), sprite_id.id(), transform, |argb| {
+            palette.map(argb)
+        });
+    }
+
+    /// same as `draw_tile_palette` with the current colors of the cycle, and the tile is
+    /// remembered by the cycle to be redrawn by `redraw_cycled`. drawing on the same tile again
+    /// replaces the remembered sprite
+    pub fn draw_tile_cycled(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        sprite_id: impl SpriteId,
+        cycle: &mut PaletteCycle,
+    ) {
+        let sprite_id = sprite_id.id();
+        self.draw_tile_palette((tile_x, tile_y), sprite_id, &cycle.palette());
+        cycle.tiles.insert((tile_x, tile_y), sprite_id);
+    }
+
+    /// redraws the tiles drawn with `draw_tile_cycled` with the current colors of the cycle,
+    /// leaving the rest of the grid as it is
+    pub fn redraw_cycled(&mut self, cycle: &PaletteCycle) {
+        let palette = cycle.palette();
+        for (&position, &sprite_id) in &cycle.tiles {
+            self.draw_tile_palette(position, sprite_id, &palette);
+        }
+    }
+}
@@ -0,0 +1,766 @@
+//! effects applied to the whole frame after it's drawn, to change the mood of a scene without
+//! touching the sprites
+
+use std::any::Any;
+use std::path::Path;
+
+use image::{DynamicImage, ImageReader, Rgb, RgbImage};
+
+use crate::{Bitmap, Color, PixelGrid, PixelStorage, TileGrid, TileyError};
+
+/// an effect applied to the whole bitmap after the frame is drawn, it can be added to the
+/// `TileGrid::post_pipeline` to be applied to every frame, or applied once with
+/// `TileGrid::apply_post_effect`. the functions of a bitmap are effects too
+///
+/// # Examples
+///
+/// ```
+/// use tiley::{Bitmap, SpriteSheet, TileGrid};
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+/// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+///
+/// let invert = |bitmap: &mut Bitmap| bitmap.map_pixels(|_, _, color| !color & 0xffffff);
+///
+/// tile_grid.bitmap_mut().fill(0x204080);
+/// tile_grid.apply_post_effect(&invert);
+/// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xdfbf7f);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+pub trait PostEffect<S = Vec<u32>> {
+    fn apply(&self, bitmap: &mut Bitmap<S>, context: &PostContext);
+}
+
+/// where the virtual pixels of the grid are in the bitmap, for the effects that follow them, like
+/// the scanlines of the `CrtFilter`
+#[derive(Debug, Clone, Copy)]
+pub struct PostContext<'a> {
+    column_starts: &'a [usize],
+    row_starts: &'a [usize],
+}
+
+/// effects applied one after the other, to every frame when it's the `TileGrid::post_pipeline`
+pub struct PostPipeline<S = Vec<u32>> {
+    effects: Vec<Box<dyn AnyEffect<S>>>,
+}
+
+/// an effect that can be found again in the pipeline by its type
+trait AnyEffect<S>: PostEffect<S> {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<S, T: PostEffect<S> + 'static> AnyEffect<S> for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// changes the brightness, the contrast and the gamma, see `TileGrid::post_adjust`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Adjust {
+    /// from -1.0 for black to 1.0 for white
+    pub brightness: f32,
+    /// a factor with 0.0 for a flat gray
+    pub contrast: f32,
+    /// positive, over 1.0 the dark colors get lighter
+    pub gamma: f32,
+}
+
+/// darkens the edges of the bitmap, see `TileGrid::post_vignette`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vignette {
+    /// where the darkening starts, from 0.0 in the center to 1.0 in the corners
+    pub radius: f32,
+    /// how dark the corners get, from 0.0 to 1.0 for black
+    pub strength: f32,
+}
+
+/// multiplies the bitmap by a color, see `TileGrid::set_ambient_tint`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientTint {
+    /// `0xRRGGBB` color, white changes nothing
+    pub color: u32,
+    /// from 0.0 for no change to 1.0 for fully multiplied
+    pub strength: f32,
+}
+
+/// a color lookup table, to give a scene a look like "night" or "sepia" made in an image editor.
+/// the table is an image, either a strip of `size` squares of `size`x`size` pixels, or a single
+/// row of pixels
+///
+/// in the strip the red grows from left to right in every square, the green from top to bottom
+/// and the blue from the first square to the last. the colors between the ones in the table are
+/// interpolated, so a small table like 16x16x16 is enough. the easiest way to make one is to
+/// save `ColorLut::identity(16).to_image()`, that doesn't change the colors, and color grade it
+///
+/// in a single row of pixels the three channels are changed separately, the red of a pixel is
+/// looked up in the red of the row, from the left for 0 to the right for 255, the same for the
+/// green and the blue
+///
+/// # Examples
+///
+/// ```
+/// use tiley::{Bitmap, ColorLut, SpriteSheet, TileGrid};
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+/// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+///
+/// // a table that keeps only the red
+/// let mut image = ColorLut::identity(4).to_image();
+/// for pixel in image.pixels_mut() {
+///     pixel.0[1] = 0;
+///     pixel.0[2] = 0;
+/// }
+/// let red_only = ColorLut::from_image(image)?;
+///
+/// tile_grid.bitmap_mut().fill(0x80c0ff);
+/// tile_grid.post_color_lut(&red_only);
+/// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x800000);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColorLut {
+    table: LutTable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LutTable {
+    /// the new value of every channel, for every value of the channel
+    Curves(Box<[[u8; 256]; 3]>),
+    /// the colors for `size` steps of every channel, the red changes first, then the green
+    Cube { size: usize, colors: Vec<[u8; 3]> },
+}
+
+/// the look of an old crt screen, an effect usually put last in the `TileGrid::post_pipeline`.
+/// the last row of screen pixels of every row of virtual pixels is darkened, like the gaps
+/// between the scanlines, and the columns of screen pixels are tinted red, green and blue in
+/// turn, like the phosphors of the screen
+///
+/// # Examples
+///
+/// ```
+/// use tiley::{Bitmap, CrtFilter, SpriteSheet, TileGrid};
+///
+/// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+/// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+/// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+///
+/// tile_grid.post_pipeline().push(CrtFilter {
+///     scanlines: 0.5,
+///     mask: 0.0,
+/// });
+///
+/// tile_grid.bitmap_mut().fill(0xffffff);
+/// tile_grid.present();
+/// // the virtual pixels are 2.5 screen pixels tall, the first row ends on the screen row 1
+/// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xffffff);
+/// assert_eq!(tile_grid.bitmap().get_pixel((0, 1)), 0x808080);
+/// # Ok::<(), tiley::TileyError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrtFilter {
+    /// how much the gaps between the scanlines are darkened, from 0.0 to 1.0 for black
+    pub scanlines: f32,
+    /// how much the two other channels of every column are darkened, from 0.0 to 1.0
+    pub mask: f32,
+}
+
+impl Default for CrtFilter {
+    /// visible scanlines and a mild pixel mask
+    fn default() -> Self {
+        CrtFilter {
+            scanlines: 0.4,
+            mask: 0.15,
+        }
+    }
+}
+
+impl<'a> PostContext<'a> {
+    /// the first screen column of every column of virtual pixels, followed by the one after the
+    /// last
+    pub fn column_starts(&self) -> &'a [usize] {
+        self.column_starts
+    }
+
+    /// the first screen row of every row of virtual pixels, followed by the one after the last
+    pub fn row_starts(&self) -> &'a [usize] {
+        self.row_starts
+    }
+}
+
+impl<S: PixelStorage> PostPipeline<S> {
+    pub fn new() -> Self {
+        PostPipeline {
+            effects: Vec::new(),
+        }
+    }
+
+    /// adds an effect after the ones already in the pipeline
+    pub fn push(&mut self, effect: impl PostEffect<S> + 'static) -> &mut Self {
+        self.effects.push(Box::new(effect));
+        self
+    }
+
+    /// adds an effect before the one at an index, or at the end with the length of the pipeline
+    ///
+    /// # Panics
+    ///
+    /// panics if the index is greater than the length of the pipeline
+    pub fn insert(&mut self, index: usize, effect: impl PostEffect<S> + 'static) -> &mut Self {
+        self.effects.insert(index, Box::new(effect));
+        self
+    }
+
+    /// the first effect of a type in the pipeline, if there is one
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.effects
+            .iter()
+            .find_map(|effect| effect.as_any().downcast_ref())
+    }
+
+    /// the first effect of a type in the pipeline, to change it between two frames, like the
+    /// color of an `AmbientTint` during a day and night cycle
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{AmbientTint, Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.post_pipeline().push(AmbientTint {
+    ///     color: 0xffffff,
+    ///     strength: 1.0,
+    /// });
+    ///
+    /// // the sun sets
+    /// if let Some(tint) = tile_grid.post_pipeline().get_mut::<AmbientTint>() {
+    ///     tint.color = 0xff8040;
+    /// }
+    ///
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xff8040);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.effects
+            .iter_mut()
+            .find_map(|effect| effect.as_any_mut().downcast_mut())
+    }
+
+    /// removes every effect of a type from the pipeline
+    pub fn remove<T: 'static>(&mut self) {
+        self.effects.retain(|effect| !effect.as_any().is::<T>());
+    }
+
+    /// removes every effect
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+
+    /// number of effects in the pipeline
+    pub fn len(&self) -> usize {
+        self.effects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+}
+
+impl<S: PixelStorage> Default for PostPipeline<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for PostPipeline<S> {
+    fn apply(&self, bitmap: &mut Bitmap<S>, context: &PostContext) {
+        for effect in &self.effects {
+            effect.apply(bitmap, context);
+        }
+    }
+}
+
+impl<S: PixelStorage, F: Fn(&mut Bitmap<S>)> PostEffect<S> for F {
+    fn apply(&self, bitmap: &mut Bitmap<S>, _context: &PostContext) {
+        self(bitmap);
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for Adjust {
+    /// # Panics
+    ///
+    /// panics if the gamma is not positive, in debug builds or with the `bounds-checks` feature
+    fn apply(&self, bitmap: &mut Bitmap<S>, _context: &PostContext) {
+        let Adjust {
+            brightness,
+            contrast,
+            gamma,
+        } = *self;
+        check!(gamma > 0.0, "the gamma {gamma} is not positive");
+
+        // the same curve for the three channels, computed once for every value of a channel
+        let lut: [u8; 256] = std::array::from_fn(|value| {
+            let value = (value as f32 / 255.0).powf(1.0 / gamma);
+            let value = (value - 0.5) * contrast + 0.5 + brightness;
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        });
+
+        bitmap.map_pixels(|_, _, color| {
+            let [_, r, g, b] = color.to_be_bytes();
+            u32::from_be_bytes([0, lut[r as usize], lut[g as usize], lut[b as usize]])
+        });
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for Vignette {
+    fn apply(&self, bitmap: &mut Bitmap<S>, _context: &PostContext) {
+        let (radius, strength) = (self.radius, self.strength.clamp(0.0, 1.0));
+        let (width, height) = bitmap.dimensions();
+        let (half_width, half_height) = (width as f32 / 2.0, height as f32 / 2.0);
+
+        bitmap.map_pixels(|x, y, color| {
+            // the distance from the center, stretched to an ellipse as wide as the bitmap, with
+            // the corners at 1.0
+            let (dx, dy) = (
+                (x as f32 + 0.5 - half_width) / half_width,
+                (y as f32 + 0.5 - half_height) / half_height,
+            );
+            let distance = (dx * dx + dy * dy).sqrt() / std::f32::consts::SQRT_2;
+            if distance <= radius {
+                return color;
+            }
+
+            // smoothstep from the radius to the corners
+            let t = ((distance - radius) / (1.0 - radius)).clamp(0.0, 1.0);
+            let factor = 1.0 - strength * t * t * (3.0 - 2.0 * t);
+
+            let [_, r, g, b] = color.to_be_bytes();
+            let channel = |c: u8| (c as f32 * factor).round() as u8;
+            u32::from_be_bytes([0, channel(r), channel(g), channel(b)])
+        });
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for AmbientTint {
+    fn apply(&self, bitmap: &mut Bitmap<S>, _context: &PostContext) {
+        let strength = self.strength.clamp(0.0, 1.0);
+        if strength == 0.0 || self.color & 0xffffff == 0xffffff {
+            return;
+        }
+
+        // a channel is scaled by the same factor for every pixel, so it's computed once for
+        // every value of the channel
+        let [_, r, g, b] = self.color.to_be_bytes();
+        let [r_lut, g_lut, b_lut] = [r, g, b].map(|tint| {
+            let factor = 1.0 - strength + strength * tint as f32 / 255.0;
+            let lut: [u8; 256] = std::array::from_fn(|value| (value as f32 * factor).round() as u8);
+            lut
+        });
+
+        bitmap.map_pixels(|_, _, color| {
+            let [_, r, g, b] = color.to_be_bytes();
+            u32::from_be_bytes([0, r_lut[r as usize], g_lut[g as usize], b_lut[b as usize]])
+        });
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for ColorLut {
+    fn apply(&self, bitmap: &mut Bitmap<S>, _context: &PostContext) {
+        bitmap.map_pixels(|_, _, color| self.map(color));
+    }
+}
+
+impl<S: PixelStorage> PostEffect<S> for CrtFilter {
+    fn apply(&self, bitmap: &mut Bitmap<S>, context: &PostContext) {
+        // the gap is the last screen row of every row of virtual pixels, or every other row when
+        // the virtual pixels are a single screen pixel tall
+        let (_, height) = bitmap.dimensions();
+        let row_starts = context.row_starts();
+        let mut gaps = vec![false; height];
+        for row in row_starts.windows(2) {
+            match row[1] - row[0] {
+                0 => {}
+                1 => gaps[row[0]] = (row[0] - row_starts[0]) % 2 == 1,
+                _ => gaps[row[1] - 1] = true,
+            }
+        }
+
+        let scanline = 1.0 - self.scanlines.clamp(0.0, 1.0);
+        let mask = 1.0 - self.mask.clamp(0.0, 1.0);
+        bitmap.map_pixels(|x, y, color| {
+            let row_factor = if gaps[y] { scanline } else { 1.0 };
+            let channels = color.to_be_bytes();
+            let [r, g, b] = [1, 2, 3].map(|channel| {
+                let factor = match channel == x % 3 + 1 {
+                    true => row_factor,
+                    false => row_factor * mask,
+                };
+                (channels[channel] as f32 * factor).round() as u8
+            });
+
+            u32::from_be_bytes([0, r, g, b])
+        });
+    }
+}
+
+impl ColorLut {
+    /// loads a color lookup table from an image file
+    ///
+    /// # Errors
+    ///
+    /// fails if the image can't be opened or decoded, or if it's not a table
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, TileyError> {
+        Self::from_image(ImageReader::open(path)?.decode()?)
+    }
+
+    /// a color lookup table from an image, a strip of squares or a single row
+    ///
+    /// # Errors
+    ///
+    /// fails if the image is not a strip of squares as wide as the strip is tall, or a row of at
+    /// least two pixels
+    pub fn from_image(image: impl Into<DynamicImage>) -> Result<Self, TileyError> {
+        let image = image.into().into_rgb8();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+
+        if height == 1 && width >= 2 {
+            // the row is stretched over the 256 values of a channel
+            let curves = std::array::from_fn(|channel| {
+                std::array::from_fn(|value| {
+                    let position = value as f32 * (width - 1) as f32 / 255.0;
+                    let (left, t) = (position as usize, position.fract());
+                    let right = (left + 1).min(width - 1);
+                    let (a, b) = (
+                        image.get_pixel(left as u32, 0).0[channel] as f32,
+                        image.get_pixel(right as u32, 0).0[channel] as f32,
+                    );
+                    (a + (b - a) * t).round() as u8
+                })
+            });
+
+            return Ok(ColorLut {
+                table: LutTable::Curves(Box::new(curves)),
+            });
+        }
+
+        if height < 2 || width != height * height {
+            return Err(TileyError::BadDimensions(format!(
+                "a {width}x{height} image is not a color lookup table"
+            )));
+        }
+
+        let size = height;
+        let mut colors = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    colors.push(image.get_pixel((b * size + r) as u32, g as u32).0);
+                }
+            }
+        }
+
+        Ok(ColorLut {
+            table: LutTable::Cube { size, colors },
+        })
+    }
+
+    /// a table with `size` steps for every channel that changes no color, to be saved with
+    /// `to_image` and graded in an image editor
+    ///
+    /// # Panics
+    ///
+    /// panics if the size is less than 2
+    pub fn identity(size: usize) -> Self {
+        assert!(
+            size >= 2,
+            "a color lookup table needs at least 2 steps, not {size}"
+        );
+
+        let step = |i: usize| (i * 255 / (size - 1)) as u8;
+        let mut colors = Vec::with_capacity(size * size * size);
+        for b in 0..size {
+            for g in 0..size {
+                for r in 0..size {
+                    colors.push([step(r), step(g), step(b)]);
+                }
+            }
+        }
+
+        ColorLut {
+            table: LutTable::Cube { size, colors },
+        }
+    }
+
+    /// the table as an image, in the same layout read by `from_image`
+    pub fn to_image(&self) -> RgbImage {
+        match &self.table {
+            LutTable::Curves(curves) => RgbImage::from_fn(256, 1, |x, _| {
+                Rgb(std::array::from_fn(|channel| curves[channel][x as usize]))
+            }),
+            LutTable::Cube { size, colors } => {
+                let size = *size;
+                RgbImage::from_fn((size * size) as u32, size as u32, |x, y| {
+                    let (x, y) = (x as usize, y as usize);
+                    let (r, g, b) = (x % size, y, x / size);
+                    Rgb(colors[(b * size + g) * size + r])
+                })
+            }
+        }
+    }
+
+    /// the `0xRRGGBB` color a color is changed to
+    pub fn map(&self, color: u32) -> u32 {
+        let [_, r, g, b] = color.to_be_bytes();
+
+        let [r, g, b] = match &self.table {
+            LutTable::Curves(curves) => [
+                curves[0][r as usize],
+                curves[1][g as usize],
+                curves[2][b as usize],
+            ],
+            LutTable::Cube { size, colors } => {
+                let size = *size;
+                // the position of the color between the steps of the table, for every channel
+                let steps = [r, g, b].map(|value| {
+                    let position = value as f32 * (size - 1) as f32 / 255.0;
+                    let low = (position as usize).min(size - 2);
+                    (low, position - low as f32)
+                });
+
+                // trilinear interpolation between the 8 colors of the table around the color
+                let mut mixed = [0.0f32; 3];
+                for corner in 0..8 {
+                    let mut weight = 1.0;
+                    let mut index = 0;
+                    for (channel, &(low, t)) in steps.iter().enumerate().rev() {
+                        let high = corner >> channel & 1 == 1;
+                        weight *= if high { t } else { 1.0 - t };
+                        index = index * size + low + high as usize;
+                    }
+
+                    for (mixed, value) in mixed.iter_mut().zip(colors[index]) {
+                        *mixed += value as f32 * weight;
+                    }
+                }
+
+                mixed.map(|value| value.round().clamp(0.0, 255.0) as u8)
+            }
+        };
+
+        u32::from_be_bytes([0, r, g, b])
+    }
+}
+
+impl<S: PixelStorage> TileGrid<S> {
+    /// changes the brightness, the contrast and the gamma of everything drawn on the bitmap, for
+    /// the mood of a scene or for fading in and out. the brightness goes from -1.0, black, to
+    /// 1.0, white, the contrast is a factor with 0.0 for a flat gray, and with a gamma over 1.0
+    /// the dark colors get lighter. the frame is unchanged with `(0.0, 1.0, 1.0)`
+    ///
+    /// it changes the pixels already in the bitmap, so call it at the end of the frame, after
+    /// `present`, or add an `Adjust` to the `post_pipeline` to apply it to every frame
+    ///
+    /// # Panics
+    ///
+    /// panics if the gamma is not positive, in debug builds or with the `bounds-checks` feature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.bitmap_mut().fill(0x808080);
+    /// tile_grid.present();
+    ///
+    /// // halfway through a fade to black
+    /// tile_grid.post_adjust(-0.25, 1.0, 1.0);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x404040);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn post_adjust(&mut self, brightness: f32, contrast: f32, gamma: f32) {
+        let adjust = Adjust {
+            brightness,
+            contrast,
+            gamma,
+        };
+        self.apply_post_effect(&adjust);
+    }
+
+    /// changes the colors of everything drawn on the bitmap with a color lookup table. like
+    /// `post_adjust`, call it at the end of the frame, after `present`
+    pub fn post_color_lut(&mut self, lut: &ColorLut) {
+        self.apply_post_effect(lut);
+    }
+
+    /// darkens the edges of everything drawn on the bitmap, for a cozy retro look. the radius is
+    /// where the darkening starts, from 0.0 in the center of the bitmap to 1.0 in the corners,
+    /// and the strength is how dark the corners get, from 0.0 to 1.0 for black. like
+    /// `post_adjust`, call it at the end of the frame, after `present`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{color, Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    ///
+    /// tile_grid.post_vignette(0.5, 0.75);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((300, 100)), 0xffffff);
+    /// assert!(color::channels(tile_grid.bitmap().get_pixel((0, 0))).0 < 0x48);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn post_vignette(&mut self, radius: f32, strength: f32) {
+        self.apply_post_effect(&Vignette { radius, strength });
+    }
+
+    /// applies an effect once to everything drawn on the bitmap, call it at the end of the
+    /// frame, after `present`. the effects in the `post_pipeline` are applied to every frame
+    /// instead
+    pub fn apply_post_effect(&mut self, effect: &(impl PostEffect<S> + ?Sized)) {
+        self.pixel_grid.apply_post_effect(effect);
+    }
+
+    /// multiplies every frame by an ambient color in `present`, for the day and night cycles or
+    /// for the mood of indoor scenes. with a strength of 0.0 the frame is unchanged and with 1.0
+    /// it's fully multiplied, a white tint changes nothing. the cursor is drawn after the tint,
+    /// so it keeps its colors
+    ///
+    /// the tint is an `AmbientTint` in the `post_pipeline`, this changes the first one, or adds
+    /// one at the start of the pipeline if there's none
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a blue night
+    /// tile_grid.set_ambient_tint(0x4060c0, 1.0);
+    ///
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x4060c0);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn set_ambient_tint(&mut self, color: impl Into<Color>, strength: f32) {
+        let tint = AmbientTint {
+            color: u32::from(color.into()),
+            strength: strength.clamp(0.0, 1.0),
+        };
+
+        match self.post_pipeline.get_mut::<AmbientTint>() {
+            Some(current) => *current = tint,
+            None => {
+                self.post_pipeline.insert(0, tint);
+            }
+        }
+    }
+
+    /// removes the ambient tints from the `post_pipeline`, so the frames are not tinted anymore
+    pub fn clear_ambient_tint(&mut self) {
+        self.post_pipeline.remove::<AmbientTint>();
+    }
+
+    /// the `0xRRGGBB` ambient tint with its strength, if there's one in the `post_pipeline`
+    pub fn ambient_tint(&self) -> Option<(u32, f32)> {
+        let tint = self.post_pipeline.get::<AmbientTint>()?;
+        Some((tint.color, tint.strength))
+    }
+
+    /// sets the crt filter of the `post_pipeline`, changing the first one or adding one at the
+    /// end of the pipeline if there's none, or removes the crt filters with `None`
+    pub fn set_crt_filter(&mut self, crt_filter: Option<CrtFilter>) {
+        let Some(crt_filter) = crt_filter else {
+            self.post_pipeline.remove::<CrtFilter>();
+            return;
+        };
+
+        match self.post_pipeline.get_mut::<CrtFilter>() {
+            Some(current) => *current = crt_filter,
+            None => {
+                self.post_pipeline.push(crt_filter);
+            }
+        }
+    }
+
+    /// the crt filter in the `post_pipeline`, if there's one
+    pub fn crt_filter(&self) -> Option<CrtFilter> {
+        self.post_pipeline.get().copied()
+    }
+
+    /// the effects applied to every frame by `present`, in order, before the cursor is drawn.
+    /// the order matters, a crt filter put before a tint has tinted scanlines, and one put after
+    /// a color lookup table has scanlines of the colors of the table
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Adjust, AmbientTint, Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(32, 16), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// let dusk = AmbientTint {
+    ///     color: 0x808080,
+    ///     strength: 1.0,
+    /// };
+    /// let brighter = Adjust {
+    ///     brightness: 0.5,
+    ///     contrast: 1.0,
+    ///     gamma: 1.0,
+    /// };
+    ///
+    /// // tinted, then brightened
+    /// tile_grid.post_pipeline().push(dusk).push(brighter);
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0xffffff);
+    ///
+    /// // brightened, then tinted
+    /// tile_grid.post_pipeline().clear();
+    /// tile_grid.post_pipeline().push(brighter).push(dusk);
+    /// tile_grid.bitmap_mut().fill(0xffffff);
+    /// tile_grid.present();
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x808080);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn post_pipeline(&mut self) -> &mut PostPipeline<S> {
+        &mut self.post_pipeline
+    }
+}
+
+impl<S: PixelStorage> PixelGrid<S> {
+    /// applies an effect to the bitmap, with the columns and the rows of the virtual pixels
+    pub(crate) fn apply_post_effect(&mut self, effect: &(impl PostEffect<S> + ?Sized)) {
+        let context = PostContext {
+            column_starts: &self.column_starts,
+            row_starts: &self.row_starts,
+        };
+        effect.apply(&mut self.bitmap, &context);
+    }
+}
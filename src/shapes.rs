@@ -0,0 +1,897 @@
+//! drawing of lines, shapes and fills on the pixel grid, in virtual pixels, for debug overlays,
+//! backgrounds and simple effects over the tiles
+
+use crate::{mix, Bitmap, BlendMode, Color, PixelGrid, PixelStorage, Point, Rect, TileGrid};
+
+/// how the color of a gradient changes across the area it fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Gradient {
+    /// from the left border to the right one
+    Horizontal,
+    /// from the top border to the bottom one, like a sky
+    Vertical,
+    /// from the center to the corners
+    Radial,
+}
+
+/// ordered dithering matrix used by `PixelGrid::fill_dithered`, the bigger ones can fake more
+/// shades between two colors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DitherPattern {
+    /// 2x2 matrix, 5 shades
+    Bayer2,
+    /// 4x4 matrix, 17 shades
+    #[default]
+    Bayer4,
+    /// 8x8 matrix, 65 shades
+    Bayer8,
+}
+
+/// how `TileGrid::highlight_tile` marks a tile
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HighlightStyle {
+    /// a border inside the tile, as thick as the given number of virtual pixels
+    Border(usize),
+    /// the whole tile covered by the color, with an opacity from 0.0 to 1.0 so the tile under it
+    /// stays visible
+    Fill(f32),
+}
+
+/// how `TileGrid::draw_path` shows a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathStyle {
+    /// a one virtual pixel wide line through the centers of the tiles
+    Line,
+    /// the tiles of the path covered by the color, with an opacity from 0.0 to 1.0
+    Cells(f32),
+}
+
+impl DitherPattern {
+    /// whether the pixel at `(x, y)` gets the second color when `amount` of the pixels get it
+    fn is_second(self, (x, y): (usize, usize), amount: f32) -> bool {
+        let levels = match self {
+            DitherPattern::Bayer2 => 1,
+            DitherPattern::Bayer4 => 2,
+            DitherPattern::Bayer8 => 3,
+        };
+
+        // every bit of the coordinates picks a value of the 2x2 matrix, the lowest bits are the
+        // most significant
+        let mut threshold = 0;
+        for level in 0..levels {
+            let bits = ((x >> level) & 1, (y >> level) & 1);
+            let value = match bits {
+                (0, 0) => 0,
+                (1, 0) => 2,
+                (0, 1) => 3,
+                _ => 1,
+            };
+            threshold += value << (2 * (levels - 1 - level));
+        }
+
+        let count = (1 << (2 * levels)) as f32;
+        (threshold as f32 + 0.5) / count < amount
+    }
+}
+
+impl Gradient {
+    /// the `0xRRGGBB` color of the gradient at a pixel of a `width`x`height` area
+    fn color(
+        self,
+        from: u32,
+        to: u32,
+        (x, y): (usize, usize),
+        (width, height): (usize, usize),
+    ) -> u32 {
+        // where the pixel is between the two colors, from 0.0 to 1.0
+        let fraction = |position: usize, length: usize| match length {
+            0 | 1 => 0.0,
+            _ => position as f64 / (length - 1) as f64,
+        };
+        let progress = match self {
+            Gradient::Horizontal => fraction(x, width),
+            Gradient::Vertical => fraction(y, height),
+            Gradient::Radial => {
+                let (center_x, center_y) =
+                    ((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0);
+                let radius = center_x.hypot(center_y);
+                match radius > 0.0 {
+                    true => (x as f64 - center_x).hypot(y as f64 - center_y) / radius,
+                    false => 0.0,
+                }
+            }
+        };
+
+        mix(to, from, (progress * 255.0).round() as u8)
+    }
+}
+
+impl<S: PixelStorage> Bitmap<S> {
+    /// fills the whole bitmap with a gradient between two `0xRRGGBB` colors
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Gradient};
+    ///
+    /// let mut bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// bitmap.fill_gradient(Gradient::Vertical, 0x000000, 0xffffff);
+    /// assert_eq!(bitmap.get_pixel((300, 0)), 0x000000);
+    /// assert_eq!(bitmap.get_pixel((300, 199)), 0xffffff);
+    /// ```
+    pub fn fill_gradient(
+        &mut self,
+        gradient: Gradient,
+        from: impl Into<Color>,
+        to: impl Into<Color>,
+    ) {
+        let from = u32::from(from.into());
+        let to = u32::from(to.into());
+        let (width, height) = (self.width, self.height);
+        let format = self.format;
+        let pixels = self.buffer.as_pixels_mut();
+        for y in 0..height {
+            for x in 0..width {
+                let color = gradient.color(from, to, (x, y), (width, height));
+                pixels[y * width + x] = format.pack(color);
+            }
+        }
+    }
+}
+
+impl<S: PixelStorage> PixelGrid<S> {
+    /// draws a virtual pixel of a `0xRRGGBB` color, unless it's outside the grid
+    fn plot(&mut self, (x, y): (isize, isize), color: u32) {
+        if self.bounds().contains((x, y)) {
+            self.draw_virtual_pixel_unchecked((x as usize, y as usize), color);
+        }
+    }
+
+    /// fills the whole grid with a gradient between two `0xRRGGBB` colors, one color for every
+    /// virtual pixel. the letterbox bars around the grid are left untouched
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Gradient, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a sky from light blue at the top to white at the horizon
+    /// tile_grid.pixel_grid_mut().fill_gradient(Gradient::Vertical, 0x80c0ff, 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 0)), 0x80c0ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((0, 79)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn fill_gradient(
+        &mut self,
+        gradient: Gradient,
+        from: impl Into<Color>,
+        to: impl Into<Color>,
+    ) {
+        let from = u32::from(from.into());
+        let to = u32::from(to.into());
+        let size = (self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let color = gradient.color(from, to, (x, y), size);
+                self.draw_virtual_pixel_unchecked((x, y), color);
+            }
+        }
+    }
+
+    /// fills a rectangle of virtual pixels with a pattern of two `0xRRGGBB` colors, with
+    /// `amount` from 0.0 for only `color_a` to 1.0 for only `color_b`, to fake the shades
+    /// between them. the pattern is aligned to the grid, so the rectangles drawn next to each
+    /// other match, and the part outside the grid is not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, DitherPattern, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a shadow covering a quarter of the pixels
+    /// let rect = Rect::new((0, 0), 8, 8);
+    /// tile_grid.pixel_grid_mut().fill_dithered(rect, 0xffffff, 0x000000, DitherPattern::Bayer4, 0.25);
+    /// let dark = tile_grid.bitmap().pixels().filter(|&(_, color)| color == 0x000000).count();
+    /// assert_eq!(dark, 160 * 80 - 64 + 16);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn fill_dithered(
+        &mut self,
+        rect: Rect,
+        color_a: impl Into<Color>,
+        color_b: impl Into<Color>,
+        pattern: DitherPattern,
+        amount: f32,
+    ) {
+        let color_a = u32::from(color_a.into());
+        let color_b = u32::from(color_b.into());
+        let Some(rect) = rect.intersect(&self.bounds()) else {
+            return;
+        };
+
+        let (left, top) = (rect.x as usize, rect.y as usize);
+        for y in top..top + rect.height {
+            for x in left..left + rect.width {
+                let color = match pattern.is_second((x, y), amount) {
+                    true => color_b,
+                    false => color_a,
+                };
+                self.draw_virtual_pixel_unchecked((x, y), color);
+            }
+        }
+    }
+
+    /// strokes the borders of the virtual pixels over what is drawn, with one screen pixel wide
+    /// lines of a `0xRRGGBB` color, to check the scaling and the placement of the sprites
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 600 * 200], 600, 200);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.pixel_grid_mut().draw_debug_grid(0x404040);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_debug_grid(&mut self, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        self.draw_grid_lines((1, 1), color);
+    }
+
+    /// strokes one screen pixel wide lines on the left and top borders of every `step`-th
+    /// column and row of virtual pixels, and on the right and bottom borders of the grid
+    fn draw_grid_lines(&mut self, (step_x, step_y): (usize, usize), color: u32) {
+        let (left, right) = (self.column_starts[0], self.column_starts[self.width]);
+        let (top, bottom) = (self.row_starts[0], self.row_starts[self.height]);
+        let lines = |starts: &[usize], length: usize, step: usize, end: usize| -> Vec<usize> {
+            (0..length)
+                .step_by(step)
+                .map(|i| starts[i])
+                .chain([end - 1])
+                .collect()
+        };
+
+        for x in lines(&self.column_starts, self.width, step_x, right) {
+            let line = Rect::new((x as isize, top as isize), 1, bottom - top);
+            self.bitmap.fill_rect(line, color);
+        }
+        for y in lines(&self.row_starts, self.height, step_y, bottom) {
+            let line = Rect::new((left as isize, y as isize), right - left, 1);
+            self.bitmap.fill_rect(line, color);
+        }
+    }
+
+    /// the packed pixel of the bitmap at the top left corner of a virtual pixel
+    fn packed_vpixel(&self, (x, y): (usize, usize)) -> u32 {
+        let rect = self.virtual_pixel_rect((x, y));
+        self.bitmap[(rect.x as usize, rect.y as usize)]
+    }
+
+    /// draws the virtual pixels from `left` to `right` of the row `y`, both included, the part
+    /// outside the grid is skipped
+    fn plot_span(&mut self, y: isize, left: isize, right: isize, color: u32) {
+        self.draw_rect(Rect::new((left, y), left.abs_diff(right) + 1, 1), color);
+    }
+
+    /// fills a rectangle of virtual pixels with a `0xRRGGBB` color, the part outside the grid is
+    /// not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a health bar
+    /// tile_grid.pixel_grid_mut().draw_rect(Rect::new((2, 2), 30, 3), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((31, 4)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((32, 4)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_rect(&mut self, rect: Rect, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        let Some(rect) = rect.intersect(&self.bounds()) else {
+            return;
+        };
+
+        // the virtual pixels of the rectangle cover a single rectangle of the screen
+        let first = self.virtual_pixel_rect((rect.x as usize, rect.y as usize));
+        let last = self.virtual_pixel_rect((rect.right() as usize - 1, rect.bottom() as usize - 1));
+        let screen = Rect::new(
+            (first.x, first.y),
+            last.right().abs_diff(first.x),
+            last.bottom().abs_diff(first.y),
+        );
+        self.bitmap.fill_rect(screen, color);
+    }
+
+    /// draws the border of a rectangle of virtual pixels, `thickness` virtual pixels wide
+    /// inside the rectangle, the part outside the grid is not drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.pixel_grid_mut().draw_rect_outline(Rect::new((10, 10), 20, 10), 0xffffff, 2);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((11, 15)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((12, 15)), 0x000000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((20, 18)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_rect_outline(&mut self, rect: Rect, color: impl Into<Color>, thickness: usize) {
+        let color = u32::from(color.into());
+        let Rect {
+            x,
+            y,
+            width,
+            height,
+        } = rect;
+        if 2 * thickness >= width || 2 * thickness >= height {
+            self.draw_rect(rect, color);
+            return;
+        }
+
+        let (inner_height, border) = (height - 2 * thickness, thickness as isize);
+        self.draw_rect(Rect::new((x, y), width, thickness), color);
+        self.draw_rect(
+            Rect::new((x, rect.bottom() - border), width, thickness),
+            color,
+        );
+        self.draw_rect(Rect::new((x, y + border), thickness, inner_height), color);
+        self.draw_rect(
+            Rect::new((rect.right() - border, y + border), thickness, inner_height),
+            color,
+        );
+    }
+
+    /// draws the point `(x, y)` of a shape centered on `center` mirrored on the four quadrants,
+    /// or the rows between the mirrored points when the shape is filled
+    fn plot_quadrants(
+        &mut self,
+        (center_x, center_y): (isize, isize),
+        (x, y): (isize, isize),
+        color: u32,
+        filled: bool,
+    ) {
+        match filled {
+            true => {
+                self.plot_span(center_y - y, center_x - x, center_x + x, color);
+                self.plot_span(center_y + y, center_x - x, center_x + x, color);
+            }
+            false => {
+                for (sign_x, sign_y) in [(1, 1), (-1, 1), (1, -1), (-1, -1)] {
+                    self.plot((center_x + sign_x * x, center_y + sign_y * y), color);
+                }
+            }
+        }
+    }
+
+    /// draws a one virtual pixel wide line of a `0xRRGGBB` color from `start` to `end`, both
+    /// included. the ends can be outside the grid, only the part of the line inside it is drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // an aiming line from the player to the mouse
+    /// tile_grid.pixel_grid_mut().draw_line((2, 2), (12, 7), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((2, 2)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((12, 7)), 0xff0000);
+    ///
+    /// // the part outside the grid is clipped
+    /// tile_grid.pixel_grid_mut().draw_line((-100, 5), (1000, 5), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((159, 5)), 0x00ff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_line(
+        &mut self,
+        start: (isize, isize),
+        end: (isize, isize),
+        color: impl Into<Color>,
+    ) {
+        let color = u32::from(color.into());
+        let (x1, y1) = start;
+        let (dx, dy) = (end.0 - x1, end.1 - y1);
+        let steps = dx.unsigned_abs().max(dy.unsigned_abs()) as isize;
+        if steps == 0 {
+            self.plot(start, color);
+            return;
+        }
+
+        // the steps that can be inside the grid, so a line coming from far away doesn't walk
+        // all the pixels outside of it
+        let (mut first, mut last) = (0, steps);
+        for (from, delta, length) in [(x1, dx, self.width), (y1, dy, self.height)] {
+            if delta == 0 {
+                continue;
+            }
+
+            let step_at = |position: isize| (position - from) as f64 * steps as f64 / delta as f64;
+            let (a, b) = (step_at(-1), step_at(length as isize));
+            first = first.max(a.min(b).floor() as isize);
+            last = last.min(a.max(b).ceil() as isize);
+        }
+
+        // the position on an axis after `step` steps, rounded to the nearest virtual pixel
+        let at = |from: isize, delta: isize, step: isize| {
+            let (delta, step, steps) = (delta as i128, step as i128, steps as i128);
+            from + (2 * step * delta + steps).div_euclid(2 * steps) as isize
+        };
+        for step in first..=last {
+            self.plot((at(x1, dx, step), at(y1, dy, step)), color);
+        }
+    }
+
+    /// draws a circle of a `0xRRGGBB` color with the midpoint algorithm, only its border or
+    /// also its inside when `filled`. the circle can go past the borders of the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // the radius of an explosion
+    /// tile_grid.pixel_grid_mut().draw_circle((40, 40), 10, 0xff8000, true);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((40, 40)), 0xff8000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((50, 40)), 0xff8000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((51, 40)), 0x000000);
+    ///
+    /// // a selection ring around a unit
+    /// tile_grid.pixel_grid_mut().draw_circle((100, 40), 6, 0x00ff00, false);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((100, 34)), 0x00ff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((100, 40)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_circle(
+        &mut self,
+        center: (isize, isize),
+        radius: usize,
+        color: impl Into<Color>,
+        filled: bool,
+    ) {
+        let color = u32::from(color.into());
+        let (mut x, mut y) = (radius as isize, 0);
+        let mut error = 1 - x;
+
+        // every step draws the points of two octants of each quadrant
+        while x >= y {
+            self.plot_quadrants(center, (x, y), color, filled);
+            self.plot_quadrants(center, (y, x), color, filled);
+
+            y += 1;
+            if error < 0 {
+                error += 2 * y + 1;
+            } else {
+                x -= 1;
+                error += 2 * (y - x) + 1;
+            }
+        }
+    }
+
+    /// same as `draw_circle`, but with a different radius on the x and y axes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a blip on the minimap, twice as wide as it's tall
+    /// tile_grid.pixel_grid_mut().draw_ellipse((40, 40), (8, 4), 0xffff00, false);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((48, 40)), 0xffff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((40, 36)), 0xffff00);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_ellipse(
+        &mut self,
+        center: (isize, isize),
+        (radius_x, radius_y): (usize, usize),
+        color: impl Into<Color>,
+        filled: bool,
+    ) {
+        let color = u32::from(color.into());
+        if radius_y == 0 {
+            let (center_x, center_y) = center;
+            let radius_x = radius_x as isize;
+            self.plot_span(center_y, center_x - radius_x, center_x + radius_x, color);
+            return;
+        }
+
+        // the decision variables are multiplied by 4 to stay integers
+        let (rx2, ry2) = (
+            radius_x as i128 * radius_x as i128,
+            radius_y as i128 * radius_y as i128,
+        );
+        let (mut x, mut y) = (0, radius_y as i128);
+        let (mut dx, mut dy) = (0, 2 * rx2 * y);
+
+        // the part where the border is closer to horizontal, x moves every step
+        let mut decision = 4 * ry2 - 4 * rx2 * y + rx2;
+        while dx < dy {
+            self.plot_quadrants(center, (x as isize, y as isize), color, filled);
+
+            x += 1;
+            dx += 2 * ry2;
+            if decision < 0 {
+                decision += 4 * (dx + ry2);
+            } else {
+                y -= 1;
+                dy -= 2 * rx2;
+                decision += 4 * (dx - dy + ry2);
+            }
+        }
+
+        // the part where the border is closer to vertical, y moves every step
+        let mut decision =
+            ry2 * (2 * x + 1) * (2 * x + 1) + 4 * rx2 * (y - 1) * (y - 1) - 4 * rx2 * ry2;
+        while y >= 0 {
+            self.plot_quadrants(center, (x as isize, y as isize), color, filled);
+
+            y -= 1;
+            dy -= 2 * rx2;
+            if decision > 0 {
+                decision += 4 * (rx2 - dy);
+            } else {
+                x += 1;
+                dx += 2 * ry2;
+                decision += 4 * (dx - dy + rx2);
+            }
+        }
+    }
+
+    /// fills a polygon with a `0xRRGGBB` color, one row at a time. the vertices are on the
+    /// corners of the virtual pixels, like the corners of a `Rect`, and the virtual pixels with
+    /// their center inside the polygon are filled. the polygon can cross itself, the parts
+    /// covered an even number of times are left empty, and the part outside the grid is not
+    /// drawn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Point, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // a triangle pointing down
+    /// let triangle = [Point::new(10, 10), Point::new(30, 10), Point::new(20, 30)];
+    /// tile_grid.pixel_grid_mut().draw_polygon(&triangle, 0x4080ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((20, 20)), 0x4080ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((11, 28)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_polygon(&mut self, vertices: &[Point], color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        if vertices.len() < 3 {
+            return;
+        }
+
+        let min_y = vertices.iter().map(|v| v.y).min().unwrap_or(0).max(0);
+        let max_y = vertices
+            .iter()
+            .map(|v| v.y)
+            .max()
+            .unwrap_or(0)
+            .min(self.height as isize);
+
+        let mut crossings = Vec::new();
+        for y in min_y..max_y {
+            // the edges are crossed at the center of the row, the edges ending on it are
+            // counted once with the half open interval
+            let center = y as f64 + 0.5;
+            crossings.clear();
+            for (i, start) in vertices.iter().enumerate() {
+                let end = vertices[(i + 1) % vertices.len()];
+                let (top, bottom) = match start.y < end.y {
+                    true => (start, &end),
+                    false => (&end, start),
+                };
+                if (top.y as f64) <= center && center < bottom.y as f64 {
+                    let t = (center - top.y as f64) / (bottom.y - top.y) as f64;
+                    crossings.push(top.x as f64 + t * (bottom.x - top.x) as f64);
+                }
+            }
+            crossings.sort_by(f64::total_cmp);
+
+            // a virtual pixel is inside when its center is between two crossings
+            for pair in crossings.chunks_exact(2) {
+                let left = (pair[0] - 0.5).ceil() as isize;
+                let right = (pair[1] - 0.5).ceil() as isize - 1;
+                if left <= right {
+                    self.plot_span(y, left, right, color);
+                }
+            }
+        }
+    }
+
+    /// fills with a `0xRRGGBB` color the area of virtual pixels of the same color around a
+    /// start one, the virtual pixels connected to it horizontally or vertically. nothing is
+    /// drawn if the start is outside the grid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// // paint the inside of a box
+    /// let pixel_grid = tile_grid.pixel_grid_mut();
+    /// pixel_grid.draw_rect_outline(Rect::new((10, 10), 20, 10), 0xffffff, 1);
+    /// pixel_grid.flood_fill((15, 15), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((28, 18)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((10, 10)), 0xffffff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((5, 5)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn flood_fill(&mut self, (x, y): (usize, usize), color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        if x >= self.width || y >= self.height {
+            return;
+        }
+
+        let target = self.packed_vpixel((x, y));
+        if target == self.bitmap.format.pack(color & 0xffffff) {
+            return;
+        }
+
+        // every seed is a virtual pixel of the area, the whole row of the area through it is
+        // filled at once and the rows above and below get a seed for every run of the area
+        let mut seeds = vec![(x, y)];
+        while let Some((x, y)) = seeds.pop() {
+            if self.packed_vpixel((x, y)) != target {
+                continue;
+            }
+
+            let mut left = x;
+            while left > 0 && self.packed_vpixel((left - 1, y)) == target {
+                left -= 1;
+            }
+            let mut right = x;
+            while right + 1 < self.width && self.packed_vpixel((right + 1, y)) == target {
+                right += 1;
+            }
+
+            self.draw_rect(
+                Rect::new((left as isize, y as isize), right - left + 1, 1),
+                color,
+            );
+
+            let rows = [y.checked_sub(1), Some(y + 1).filter(|&y| y < self.height)];
+            for row in rows.into_iter().flatten() {
+                let mut in_run = false;
+                for x in left..=right {
+                    let inside = self.packed_vpixel((x, row)) == target;
+                    if inside && !in_run {
+                        seeds.push((x, row));
+                    }
+                    in_run = inside;
+                }
+            }
+        }
+    }
+}
+
+impl<S: PixelStorage> TileGrid<S> {
+    /// same as `PixelGrid::draw_rect`, but the rectangle is in tiles, to highlight a region of
+    /// the map
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, Rect, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.draw_rect_tiles(Rect::new((1, 1), 2, 2), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((23, 23)), 0x0000ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((24, 24)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_rect_tiles(&mut self, rect: Rect, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        let rect = self.tiles_to_vpixels(rect);
+        self.pixel_grid.draw_rect(rect, color);
+    }
+
+    /// same as `PixelGrid::draw_rect_outline`, but the rectangle is in tiles, the thickness is
+    /// still in virtual pixels
+    pub fn draw_rect_outline_tiles(
+        &mut self,
+        rect: Rect,
+        color: impl Into<Color>,
+        thickness: usize,
+    ) {
+        let color = u32::from(color.into());
+        let rect = self.tiles_to_vpixels(rect);
+        self.pixel_grid.draw_rect_outline(rect, color, thickness);
+    }
+
+    /// strokes the borders of the tiles over what is drawn, with one screen pixel wide lines of
+    /// a `0xRRGGBB` color, to build level editors or find tiles drawn in the wrong place. the
+    /// borders of the virtual pixels are drawn by `PixelGrid::draw_debug_grid`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.draw_debug_grid(0xff00ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((8, 3)), 0xff00ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((159, 3)), 0xff00ff);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((9, 3)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_debug_grid(&mut self, color: impl Into<Color>) {
+        let color = u32::from(color.into());
+        let tile_dimensions = (self.tile_width, self.tile_height);
+        self.pixel_grid.draw_grid_lines(tile_dimensions, color);
+    }
+
+    /// marks a tile with a `0xRRGGBB` color, for the tile under the mouse or the selected unit
+    ///
+    /// # Panics
+    ///
+    /// panics if the tile is outside the grid, in debug builds or with the `bounds-checks`
+    /// feature
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, HighlightStyle, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// tile_grid.pixel_grid_mut().fill(0xffffff);
+    ///
+    /// // the hovered tile is darkened and the selected one gets a red border
+    /// tile_grid.highlight_tile((0, 0), 0x000000, HighlightStyle::Fill(0.5));
+    /// tile_grid.highlight_tile((2, 0), 0xff0000, HighlightStyle::Border(1));
+    /// assert_eq!(tile_grid.bitmap().get_pixel((3, 3)), 0x7f7f7f);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((16, 3)), 0xff0000);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((19, 3)), 0xffffff);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn highlight_tile(
+        &mut self,
+        (tile_x, tile_y): (usize, usize),
+        color: impl Into<Color>,
+        style: HighlightStyle,
+    ) {
+        let color = u32::from(color.into());
+        check!(
+            tile_x < self.width,
+            "tile x {tile_x} is not in 0..{}",
+            self.width
+        );
+        check!(
+            tile_y < self.height,
+            "tile y {tile_y} is not in 0..{}",
+            self.height
+        );
+
+        let tile = Rect::new((tile_x as isize, tile_y as isize), 1, 1);
+        match style {
+            HighlightStyle::Border(thickness) => {
+                self.draw_rect_outline_tiles(tile, color, thickness);
+            }
+            HighlightStyle::Fill(opacity) => {
+                let opacity = (opacity.clamp(0.0, 1.0) * 255.0).round() as u8;
+                let screen = self.screen_rect_of((tile_x, tile_y));
+                let argb = 0xff000000 | color;
+                let bitmap = &mut self.pixel_grid.bitmap;
+                bitmap.blend_rect(screen, argb, opacity, &BlendMode::Normal);
+            }
+        }
+    }
+
+    /// draws a path over the tiles, given as the tiles it goes through in order, for movement
+    /// previews or to debug a pathfinding
+    ///
+    /// # Panics
+    ///
+    /// with `PathStyle::Cells`, panics if a tile is outside the grid, in debug builds or with
+    /// the `bounds-checks` feature. the lines are clipped instead
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tiley::{Bitmap, PathStyle, SpriteSheet, TileGrid};
+    ///
+    /// let bitmap = Bitmap::from_vec(vec![0; 160 * 80], 160, 80);
+    /// let sprite_sheet = SpriteSheet::from_image(image::RgbImage::new(8, 8), 8)?;
+    /// let mut tile_grid = TileGrid::with_sprite_sheet(bitmap, 20, 10, sprite_sheet)?;
+    ///
+    /// let path = [(1, 1), (2, 1), (3, 1), (3, 2)];
+    /// tile_grid.draw_path(&path, 0xffff00, PathStyle::Line);
+    /// // the line goes from the center of the first tile to the center of the last one
+    /// assert_eq!(tile_grid.bitmap().get_pixel((12, 12)), 0xffff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((28, 18)), 0xffff00);
+    /// assert_eq!(tile_grid.bitmap().get_pixel((28, 21)), 0x000000);
+    /// # Ok::<(), tiley::TileyError>(())
+    /// ```
+    pub fn draw_path(
+        &mut self,
+        tiles: &[(usize, usize)],
+        color: impl Into<Color>,
+        style: PathStyle,
+    ) {
+        let color = u32::from(color.into());
+        match style {
+            PathStyle::Line => {
+                let (width, height) = (self.tile_width, self.tile_height);
+                let center = |(x, y): (usize, usize)| {
+                    (
+                        (x * width + width / 2) as isize,
+                        (y * height + height / 2) as isize,
+                    )
+                };
+
+                if let [tile] = tiles {
+                    self.pixel_grid.plot(center(*tile), color);
+                }
+                for pair in tiles.windows(2) {
+                    let (start, end) = (center(pair[0]), center(pair[1]));
+                    self.pixel_grid.draw_line(start, end, color);
+                }
+            }
+            PathStyle::Cells(opacity) => {
+                for &tile in tiles {
+                    self.highlight_tile(tile, color, HighlightStyle::Fill(opacity));
+                }
+            }
+        }
+    }
+
+    /// the virtual pixels covered by a rectangle of tiles
+    fn tiles_to_vpixels(&self, rect: Rect) -> Rect {
+        let (width, height) = (self.tile_width, self.tile_height);
+        Rect::new(
+            (rect.x * width as isize, rect.y * height as isize),
+            rect.width * width,
+            rect.height * height,
+        )
+    }
+}